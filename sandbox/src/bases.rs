@@ -1,27 +1,45 @@
 use uuid::Uuid;
 
-#[derive(Debug, Clone)]
+use crate::rng;
+
+//fifth base means up to four runners plus the batter,
+//and runners who scored sit around until they get swept
+const MAX_RUNNERS: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
 pub struct Baserunner {
     pub id: Uuid,
     pub base: u8,
 }
 
-#[derive(Debug, Clone)]
+//runners are stored inline so the sim can copy these around on every pitch
+//without allocating. order is the order runners were added in, pick_runner depends on it
+#[derive(Debug, Clone, Copy)]
 pub struct Baserunners {
-    pub runners: Vec<Baserunner>,
+    runners: [Baserunner; MAX_RUNNERS],
+    count: usize,
     pub base_number: u8,
 }
 
 impl Baserunners {
     pub fn new(bn: u8) -> Baserunners {
         Baserunners {
-            runners: Vec::new(),
+            runners: [Baserunner { id: Uuid::nil(), base: 0 }; MAX_RUNNERS],
+            count: 0,
             base_number: bn
         }
     }
 
+    fn slots(&self) -> &[Baserunner] {
+        &self.runners[..self.count]
+    }
+
+    fn slots_mut(&mut self) -> &mut [Baserunner] {
+        &mut self.runners[..self.count]
+    }
+
     pub fn occupied(&self, base: u8) -> bool {
-        self.runners.iter().any(|x| x.base == base)
+        self.slots().iter().any(|x| x.base == base)
     }
 
     pub fn can_advance(&self, base: u8) -> bool {
@@ -29,18 +47,33 @@ impl Baserunners {
     }
 
     pub fn at(&self, base: u8) -> Option<Uuid> {
-        self.runners
+        self.slots()
             .iter()
             .find(|x| x.base == base)
             .map(|x| x.id.clone())
     }
 
+    //who's on base, lead runner first. the sim only ever adds runners behind everyone else,
+    //so in a game this is also the order they were added in
+    pub fn occupants(&self) -> Vec<Uuid> {
+        let mut slots = self.slots().to_vec();
+        slots.sort_by_key(|r| std::cmp::Reverse(r.base));
+        slots.into_iter().map(|r| r.id).collect()
+    }
+
     pub fn contains(&self, id: Uuid) -> bool {
-        self.runners.iter().any(|x| x.id == id)
+        self.slots().iter().any(|x| x.id == id)
     }
 
+    //how these work, since the run scoring depends on it:
+    //bases count from 0 (first), base_number - 1 is home. nothing here scores or removes
+    //anyone, runners at home or past it are left for Game::score and base_sweep.
+    //none of them reorder the runners either
+
+    //moves the runner on `base` up one, whether or not someone's already there.
+    //does nothing if the base is empty
     pub fn advance(&mut self, base: u8) {
-        for r in self.runners.iter_mut() {
+        for r in self.slots_mut().iter_mut() {
             if r.base == base {
                 r.base += 1;
                 return;
@@ -50,26 +83,31 @@ impl Baserunners {
 
     pub fn remove(&mut self, base: u8) -> Option<Uuid> {
         let idx = self
-            .runners
+            .slots()
             .iter()
-            .enumerate()
-            .find(|x| x.1.base == base)
-            .map(|x| x.0);
+            .position(|x| x.base == base);
 
         if let Some(idx) = idx {
-            let runner = self.runners.remove(idx);
+            let runner = self.runners[idx];
+            //shift everyone after it down to keep the order
+            self.runners.copy_within(idx + 1..self.count, idx);
+            self.count -= 1;
             Some(runner.id)
         } else {
             None
         }
     }
 
+    //moves every runner up `amount` bases, no questions asked. this is the forced part of a hit:
+    //on a double everyone gets at least two
     pub fn advance_all(&mut self, amount: u8) {
-        for r in self.runners.iter_mut() {
+        for r in self.slots_mut().iter_mut() {
             r.base += amount;
         }
     }
 
+    //makes room at first for a walked batter (who isn't added here):
+    //the unbroken chain of runners starting at first moves up one, anyone after a gap stays put
     pub fn walk(&mut self) {
         // todo: this code is also crap
         let mut num_occupied = 0;
@@ -86,23 +124,36 @@ impl Baserunners {
         }
     }
 
+    //same as walk, but for a batter going to second (or third), see walk_to
     pub fn walk_instincts(&mut self, third: bool) {
-        //todo: the runners who score end up on the wrong base
-        //does this cause problems?
-        //yes past me it does cause problems because of fifth base
-        if third {
-            self.advance_all(3);
-        } else {
-            if self.occupied(0) {
-                self.advance_all(2);
-            } else if self.occupied(1) {
-                self.advance_all(1);
-            }
+        self.walk_to(if third { 2 } else { 1 });
+    }
+
+    //makes room for a batter walking to `base`: everyone on or behind it
+    //gets pushed ahead of the batter, forcing whoever's in the way further up.
+    //runners pushed past the last base are left there to score and get swept
+    pub fn walk_to(&mut self, base: u8) {
+        //go by base order without reordering the runners themselves,
+        //pick_runner depends on that order
+        let mut order: Vec<usize> = (0..self.count).collect();
+        order.sort_by_key(|&i| self.runners[i].base);
+        let (behind, ahead): (Vec<usize>, Vec<usize>) = order
+            .into_iter()
+            .partition(|&i| self.runners[i].base <= base);
+        let mut next_free = base + 1;
+        for i in behind.into_iter().chain(ahead) {
+            let new_base = self.runners[i].base.max(next_free);
+            self.runners[i].base = new_base;
+            next_free = new_base + 1;
         }
     }
 
+    //the optional part of a hit or an out: every runner `f` picks moves up one base
+    //if the base ahead is free right then. goes through runners in the order they were added,
+    //so a runner stuck behind someone who only moves later in the pass stays stuck.
+    //after advance_all(n) this is the "one extra base" on top
     pub fn advance_if(&mut self, f: impl Fn(&Baserunner) -> bool) {
-        for i in 0..self.runners.len() {
+        for i in 0..self.count {
             if self.can_advance(self.runners[i].base) {
                 if f(&self.runners[i]) {
                     self.runners[i].base += 1;
@@ -113,11 +164,11 @@ impl Baserunners {
 
     pub fn forced_advance_if(&mut self, f: impl Fn(&Baserunner) -> bool) {
         if self.occupied(0) && self.occupied(1) && (self.base_number == 4 || self.occupied(2)) {
-            for runner in self.runners.iter_mut() {
+            for runner in self.slots_mut().iter_mut() {
                 runner.base += 1;
             }
         } else {
-            for i in 0..self.runners.len() {
+            for i in 0..self.count {
                 if self.can_advance(self.runners[i].base) {
                     if f(&self.runners[i]) {
                         self.runners[i].base += 1;
@@ -129,21 +180,34 @@ impl Baserunners {
         }
     }
 
+    //swaps who's on a base without moving anybody, for when a runner gets replaced mid-play
+    pub fn replace(&mut self, id: Uuid, new_id: Uuid) {
+        for r in self.slots_mut().iter_mut() {
+            if r.id == id {
+                r.id = new_id;
+            }
+        }
+    }
+
     pub fn add(&mut self, base: u8, id: Uuid) {
-        self.runners.push(Baserunner { id, base });
+        if self.count == MAX_RUNNERS {
+            panic!("too many runners");
+        }
+        self.runners[self.count] = Baserunner { id, base };
+        self.count += 1;
     }
 
     pub fn empty(&self) -> bool {
-        self.runners.len() == 0
+        self.count == 0
     }
 
     pub fn len(&self) -> usize {
-        self.runners.len()
+        self.count
     }
 
     pub fn pick_runner(&self, roll: f64) -> u8 {
         //todo: maybe rewrite this to use an if
-        let len = self.runners.len();
+        let len = self.count;
         match len {
             0 => {
                 panic!("this shouldn't be called");
@@ -152,33 +216,266 @@ impl Baserunners {
                 return self.runners[0].base;
             },
             _ => {
-                let idx = (roll * (len as f64)).floor() as usize;
+                let idx = rng::index(roll, len);
                 return self.runners[idx].base;
             }
         }
     }
 
+    //fielder's choices and double plays always get the lead forced runner,
+    //the last one in the unbroken chain of runners starting at first
+    //(runners on first and second: the one on second).
+    //defense doesn't come into it (yet). do_pitch still makes the roll pick_runner used to take
     pub fn pick_runner_fc(&self) -> u8 {
-        if self.occupied(1) {
-            if self.occupied(2) {
-                return 2;
-            } else {
-                return 1;
-            }
-        } else {
-            return 0;
+        let mut base = 0;
+        while self.occupied(base + 1) {
+            base += 1;
         }
+        base
+    }
+
+    //whether these runners could have come out of `before` in one play:
+    //same base count, nobody new and nobody moving backwards.
+    //runners can disappear (outs, scoring) so that's allowed
+    pub fn follows_from(&self, before: &Baserunners) -> bool {
+        self.base_number == before.base_number
+            && self.slots().iter().all(|r| {
+                before.slots().iter().any(|b| b.id == r.id && b.base <= r.base)
+            })
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &Baserunner> {
-        self.runners.iter()
+        self.slots().iter()
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Baserunner> {
-        self.runners.iter_mut()
+        self.slots_mut().iter_mut()
     }
 
     pub fn clear(&mut self) {
-        self.runners = Vec::new();
+        self.count = 0;
+    }
+}
+
+//which runners are advancing on a play, worked out in do_pitch.
+//inline like Baserunners so a pitch with runners on doesn't allocate
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunnerSet {
+    ids: [Uuid; MAX_RUNNERS],
+    count: usize,
+}
+
+impl RunnerSet {
+    pub fn new() -> RunnerSet {
+        RunnerSet::default()
+    }
+
+    pub fn push(&mut self, id: Uuid) {
+        if self.count == MAX_RUNNERS {
+            panic!("too many runners");
+        }
+        self.ids[self.count] = id;
+        self.count += 1;
+    }
+
+    pub fn contains(&self, id: &Uuid) -> bool {
+        self.ids[..self.count].contains(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //runners added in the order given, ids are just 1, 2, 3... so they're easy to tell apart
+    fn runners(bases: &[u8]) -> (Baserunners, Vec<Uuid>) {
+        let mut r = Baserunners::new(4);
+        let mut ids = Vec::new();
+        for (i, &base) in bases.iter().enumerate() {
+            let id = Uuid::from_u128(i as u128 + 1);
+            r.add(base, id);
+            ids.push(id);
+        }
+        (r, ids)
+    }
+
+    fn bases(r: &Baserunners) -> Vec<u8> {
+        r.iter().map(|x| x.base).collect()
+    }
+
+    #[test]
+    fn advance_moves_one_runner() {
+        let (mut r, _) = runners(&[1, 0]);
+        r.advance(0);
+        assert_eq!(bases(&r), vec![1, 1]);
+        r.advance(2);
+        assert_eq!(bases(&r), vec![1, 1]);
+    }
+
+    #[test]
+    fn advance_all_moves_everyone() {
+        let (mut r, _) = runners(&[2, 0]);
+        r.advance_all(2);
+        assert_eq!(bases(&r), vec![4, 2]);
+    }
+
+    #[test]
+    fn advance_if_respects_the_base_ahead() {
+        let (mut r, ids) = runners(&[1, 0]);
+        r.advance_if(|_| true);
+        assert_eq!(bases(&r), vec![2, 1]);
+
+        //the runner on first is stuck behind second, who doesn't want to go
+        let (mut r, _) = runners(&[1, 0]);
+        r.advance_if(|x| x.id != ids[0]);
+        assert_eq!(bases(&r), vec![1, 0]);
+    }
+
+    #[test]
+    fn walk_pushes_the_chain_from_first() {
+        let (mut r, _) = runners(&[1, 0]);
+        r.walk();
+        assert_eq!(bases(&r), vec![2, 1]);
+
+        //nobody on first, nobody's forced
+        let (mut r, _) = runners(&[2, 1]);
+        r.walk();
+        assert_eq!(bases(&r), vec![2, 1]);
+
+        //gap at second, the runner on third stays
+        let (mut r, _) = runners(&[2, 0]);
+        r.walk();
+        assert_eq!(bases(&r), vec![2, 1]);
+
+        let (mut r, _) = runners(&[2, 1, 0]);
+        r.walk();
+        assert_eq!(bases(&r), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn forced_advance_if_bases_loaded_moves_everyone() {
+        let (mut r, _) = runners(&[2, 1, 0]);
+        r.forced_advance_if(|_| false);
+        assert_eq!(bases(&r), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn forced_advance_if_forces_first() {
+        //runner on first doesn't want to go but has to
+        let (mut r, _) = runners(&[0]);
+        r.forced_advance_if(|_| false);
+        assert_eq!(bases(&r), vec![1]);
+
+        let (mut r, _) = runners(&[2]);
+        r.forced_advance_if(|_| false);
+        assert_eq!(bases(&r), vec![2]);
+        r.forced_advance_if(|_| true);
+        assert_eq!(bases(&r), vec![3]);
+    }
+
+    //what outcome_event does with a double play: lead forced runner out, everyone else up one
+    #[test]
+    fn double_play_removes_the_lead_forced_runner() {
+        let (mut r, ids) = runners(&[1, 0]);
+        let out = r.pick_runner_fc();
+        assert_eq!(r.remove(out), Some(ids[0]));
+        r.advance_all(1);
+        assert_eq!(bases(&r), vec![1]);
+        assert_eq!(r.at(1), Some(ids[1]));
+    }
+
+    #[test]
+    fn walk_to_pushes_everyone_ahead_of_the_batter() {
+        //batter to second, the runner on first has to go to third
+        let (mut r, _) = runners(&[0]);
+        r.walk_to(1);
+        assert_eq!(bases(&r), vec![2]);
+
+        let (mut r, _) = runners(&[2, 0]);
+        r.walk_to(1);
+        assert_eq!(bases(&r), vec![3, 2]);
+
+        //nobody in the way, nobody moves
+        let (mut r, _) = runners(&[2]);
+        r.walk_to(0);
+        assert_eq!(bases(&r), vec![2]);
+    }
+
+    #[test]
+    fn pick_runner_fc_picks_the_end_of_the_chain() {
+        let (r, _) = runners(&[0]);
+        assert_eq!(r.pick_runner_fc(), 0);
+        let (r, _) = runners(&[1, 0]);
+        assert_eq!(r.pick_runner_fc(), 1);
+        let (r, _) = runners(&[2, 0]);
+        assert_eq!(r.pick_runner_fc(), 0);
+        let (r, _) = runners(&[2, 1, 0]);
+        assert_eq!(r.pick_runner_fc(), 2);
+    }
+
+    #[test]
+    fn occupants_lead_runner_first() {
+        let (r, ids) = runners(&[0, 2, 1]);
+        assert_eq!(r.occupants(), vec![ids[1], ids[2], ids[0]]);
+        //and the storage order is left alone
+        assert_eq!(bases(&r), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn follows_from_allows_outs_but_not_going_backwards() {
+        let (before, ids) = runners(&[1, 0]);
+
+        let mut after = before;
+        after.advance_all(1);
+        assert!(after.follows_from(&before));
+
+        let mut after = before;
+        after.remove(1);
+        assert!(after.follows_from(&before));
+
+        let (after, _) = runners(&[0, 0]);
+        assert!(!after.follows_from(&before));
+
+        let mut after = before;
+        after.add(0, Uuid::from_u128(99));
+        assert!(!after.follows_from(&before));
+
+        let mut after = Baserunners::new(5);
+        after.add(1, ids[0]);
+        assert!(!after.follows_from(&before));
+    }
+
+    #[test]
+    fn replace_swaps_the_id_in_place() {
+        let (mut r, ids) = runners(&[1, 0]);
+        let new_id = Uuid::from_u128(99);
+        r.replace(ids[1], new_id);
+        assert_eq!(r.at(0), Some(new_id));
+        assert_eq!(r.at(1), Some(ids[0]));
+        assert!(!r.contains(ids[1]));
+        assert_eq!(bases(&r), vec![1, 0]);
+    }
+
+    //RunnerSet stands in for the Vec<Uuid> do_pitch used to build, it has to pick the same runners
+    #[test]
+    fn runner_set_matches_vec() {
+        let (r, ids) = runners(&[2, 1, 0]);
+        for picked in [vec![], vec![ids[0]], vec![ids[1], ids[2]], ids.clone()] {
+            let mut set = RunnerSet::new();
+            for &id in picked.iter() {
+                set.push(id);
+            }
+            for &id in ids.iter().chain([Uuid::from_u128(99)].iter()) {
+                assert_eq!(set.contains(&id), picked.contains(&id));
+            }
+
+            let mut with_vec = r;
+            with_vec.advance_all(1);
+            with_vec.advance_if(|x| picked.contains(&x.id));
+            let mut with_set = r;
+            with_set.advance_all(1);
+            with_set.advance_if(|x| set.contains(&x.id));
+            assert_eq!(bases(&with_vec), bases(&with_set));
+        }
     }
 }
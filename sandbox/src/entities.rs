@@ -1,8 +1,8 @@
-use std::{collections::BTreeMap, f64::consts::PI};
+use std::{cmp::Ordering, collections::BTreeMap, f64::consts::PI, ops::Range};
 
 use uuid::Uuid;
 
-use crate::{events::Events, mods::{Mod, ModLifetime, Mods}, rng::Rng};
+use crate::{events::Events, mods::{Mod, ModLifetime, Mods, TIRED_PENALTY, WIRED_BONUS}, rng::Rng, ruleset::Ruleset, schedule::{Schedule, DAYS_PER_WEEK}, sim::Sim, Game};
 
 #[derive(Clone, Debug)]
 pub struct World {
@@ -10,7 +10,14 @@ pub struct World {
     pub teams: BTreeMap<Uuid, Team>,
     pub stadiums: BTreeMap<Uuid, Stadium>,
     pub hall: Vec<Uuid>, //think of this as a view into a section of players
-    pub season_ruleset: u8,
+    pub replacement_pool: Vec<Player>, //prospects incineration replacements come out of before anyone gets rolled up. not in players until drawn
+    pub ruleset: Ruleset, //the season's rules, ruleset.season is the season number the formulas go by
+    pub innings: i16, //regulation game length, exhibitions can be shorter
+    pub day: usize, //the day in progress, see advance_day
+    pub foul_tips: bool, //not a blaseball thing. lets a foul with two strikes be caught for strike three
+    pub walk_offs: bool, //not a blaseball thing either. the game ends as soon as the home team takes the lead in the last inning
+    deterministic_ids: bool, //see deterministic_ids
+    mods_generation: u64, //see mods_generation
 }
 
 impl World {
@@ -20,13 +27,42 @@ impl World {
             teams: BTreeMap::new(),
             stadiums: BTreeMap::new(),
             hall: Vec::new(),
-            season_ruleset
+            replacement_pool: Vec::new(),
+            ruleset: Ruleset::new(season_ruleset),
+            innings: 9,
+            day: 0,
+            foul_tips: false,
+            walk_offs: false,
+            deterministic_ids: false,
+            mods_generation: 0,
         }
     }
+
+    //makes every id the world hands out (players, teams, games) come out of the rng
+    //instead of being random, so runs with the same seed line up exactly.
+    //that's two rolls per id, which is why it isn't the default: a regular run stays rng accurate
+    pub fn deterministic_ids(&mut self) {
+        self.deterministic_ids = true;
+    }
+
+    pub fn new_id(&self, rng: &mut Rng) -> Uuid {
+        if self.deterministic_ids {
+            rng_id(rng)
+        } else {
+            Uuid::new_v4()
+        }
+    }
+
     pub fn player(&self, id: Uuid) -> &Player {
         self.players.get(&id).unwrap()
     }
 
+    //for tooling holding ids that might not be in this world anymore, e.g. from another save.
+    //the sim itself sticks to player, a missing player there is a bug
+    pub fn try_player(&self, id: Uuid) -> Option<&Player> {
+        self.players.get(&id)
+    }
+
     pub fn team(&self, id: Uuid) -> &Team {
         self.teams.get(&id).unwrap()
     }
@@ -44,6 +80,10 @@ impl World {
         self.players.get_mut(&id).unwrap()
     }
 
+    pub fn try_player_mut(&mut self, id: Uuid) -> Option<&mut Player> {
+        self.players.get_mut(&id)
+    }
+
     pub fn team_mut(&mut self, id: Uuid) -> &mut Team {
         self.teams.get_mut(&id).unwrap()
     }
@@ -61,6 +101,18 @@ impl World {
         self.players.insert(player.id, player);
     }
 
+    //takes a prospect out of the replacement pool and puts them on `team`.
+    //false if they weren't in the pool
+    pub fn draw_from_pool(&mut self, id: Uuid, team: Uuid) -> bool {
+        let Some(idx) = self.replacement_pool.iter().position(|p| p.id == id) else {
+            return false;
+        };
+        let mut player = self.replacement_pool.remove(idx);
+        player.team = Some(team);
+        self.insert_player(player);
+        true
+    }
+
     pub fn insert_team(&mut self, team: Team) {
         self.teams.insert(team.id, team);
     }
@@ -98,22 +150,8 @@ impl World {
     }
 
     pub fn gen_team(&mut self, rng: &mut Rng, name: String, emoji: String) -> Uuid {
-        let id = Uuid::new_v4();
-        let mut team = Team {
-            id,
-            emoji,
-            lineup: Vec::new(),
-            rotation: Vec::new(),
-            shadows: Vec::new(),
-            name,
-            wins: 0,
-            losses: 0,
-            postseason_wins: 0,
-            postseason_losses: 0,
-            partying: false,
-            fate: 100,
-            mods: Mods::new(),
-        };
+        let id = self.new_id(rng);
+        let mut team = Team::new(id, name, emoji);
 
         for _ in 0..9 {
             team.lineup.push(self.gen_player(rng, id));
@@ -133,7 +171,7 @@ impl World {
 
     pub fn gen_player(&mut self, rng: &mut Rng, team: Uuid) -> Uuid {
         let interview_rolls = 6 + 2; //soul, allergy, fate, ritual, blood, coffee + names
-        let mut player = Player::new(rng);
+        let mut player = Player::new_with_id(self.new_id(rng), rng);
         let id = player.id;
         player.name = format!("Player {}", &(player.id).to_string()[..8]);
         for _ in 0..interview_rolls {
@@ -152,26 +190,154 @@ impl World {
         id
     }
 
-    pub fn random_hall_player(&self, rng: &mut Rng) -> Uuid {
+    //None if the hall is empty, doesn't roll anything in that case
+    pub fn random_hall_player(&self, rng: &mut Rng) -> Option<Uuid> {
+        if self.hall.is_empty() {
+            return None;
+        }
         let index = rng.index(self.hall.len());
-        self.hall[index]
+        Some(self.hall[index])
+    }
+
+    //plays every game on the schedule to completion, one tick per game at a time
+    //like a real day of blaseball. wins and losses get applied on GameOver
+    pub fn play_day(&mut self, schedule: &Schedule, rng: &mut Rng) -> Vec<Game> {
+        let mut games_active: Vec<Game> = schedule.matchups
+            .iter()
+            .map(|m| Game::new(m.home_team, m.away_team, schedule.day, m.weather, self, rng))
+            .collect();
+        let mut games_finished: Vec<Game> = Vec::new();
+        let mut sim = Sim::new(self, rng);
+        while !games_active.is_empty() {
+            for game in games_active.iter_mut() {
+                let evt = sim.next(game);
+                evt.apply(game, sim.world);
+            }
+            let (finished, active): (Vec<Game>, Vec<Game>) = games_active
+                .into_iter()
+                .partition(|g| g.events.total() > 0 && g.events.last() == "GameOver");
+            games_finished.extend(finished);
+            games_active = active;
+        }
+        games_finished
+    }
+
+    //for mods that work the same whether the player or their team has them.
+    //team is passed in since it's whoever they're playing for, which a ghost batter's team field won't say
+    pub fn player_or_team_has(&self, player: Uuid, team: Uuid, m: Mod) -> bool {
+        self.player(player).mods.has(m) || self.team(team).mods.has(m)
     }
 
-    pub fn clear_game(&mut self) {
-        for (_, player) in self.players.iter_mut() {
-            player.mods.clear_game();
+    pub fn give_mod(&mut self, player: Uuid, m: Mod, lifetime: ModLifetime) {
+        if self.player_mut(player).mods.add(m, lifetime) {
+            self.mods_generation += 1;
         }
     }
+
+    pub fn give_team_mod(&mut self, team: Uuid, m: Mod, lifetime: ModLifetime) {
+        if self.team_mut(team).mods.add(m, lifetime) {
+            self.mods_generation += 1;
+        }
+    }
+
+    //goes up whenever mods change through the world (give_mod, give_team_mod, the clear functions),
+    //so a game can tell the mods it cached are stale even when its own events didn't change them.
+    //changing a Mods directly through player_mut/team_mut doesn't count
+    pub fn mods_generation(&self) -> u64 {
+        self.mods_generation
+    }
+
+    //every team, best first. tiebreakers in order:
+    //1. wins
+    //2. run differential
+    //3. fate, lowest first, like the real game. head to head would go before this but nothing tracks it
+    pub fn standings(&self) -> Vec<(Uuid, Record)> {
+        let mut standings: Vec<(Uuid, Record)> = self.teams
+            .values()
+            .map(|team| (team.id, team.record()))
+            .collect();
+        standings.sort_by(|(a, ra), (b, rb)| {
+            rb.wins.cmp(&ra.wins)
+                .then(rb.run_differential.partial_cmp(&ra.run_differential).unwrap_or(Ordering::Equal))
+                .then(self.team(*a).fate.cmp(&self.team(*b).fate))
+        });
+        standings
+    }
+
+    //wraps up the current day once its games are done: game mods go away,
+    //weekly ones too at the end of a week. returns everything that expired like the clear functions.
+    //nothing elsewhere or scattered happens here, those roll during games (see ElsewherePlugin),
+    //and roaming players don't roam in the sim
+    pub fn advance_day(&mut self) -> Vec<(Uuid, Mod)> {
+        let mut expired = self.clear_game();
+        if self.day % DAYS_PER_WEEK == DAYS_PER_WEEK - 1 {
+            expired.extend(self.clear_weekly());
+        }
+        self.day += 1;
+        expired
+    }
+
+    //these return every (player, mod) that expired, players in id order
+    //and each player's mods in Mod enum order (see Mods::clear_game)
+    pub fn clear_game(&mut self) -> Vec<(Uuid, Mod)> {
+        self.clear_lifetime(Mods::clear_game)
+    }
     
-    pub fn clear_weekly(&mut self) {
-        for (_, player) in self.players.iter_mut() {
-            player.mods.clear_weekly();
+    pub fn clear_weekly(&mut self) -> Vec<(Uuid, Mod)> {
+        self.clear_lifetime(Mods::clear_weekly)
+    }
+
+    pub fn clear_season(&mut self) -> Vec<(Uuid, Mod)> {
+        self.clear_lifetime(Mods::clear_season)
+    }
+
+    fn clear_lifetime(&mut self, clear: fn(&mut Mods) -> Vec<Mod>) -> Vec<(Uuid, Mod)> {
+        let mut expired = Vec::new();
+        for (&id, player) in self.players.iter_mut() {
+            expired.extend(clear(&mut player.mods).into_iter().map(|m| (id, m)));
+        }
+        if !expired.is_empty() {
+            self.mods_generation += 1;
         }
+        expired
     }
 
-    pub fn clear_season(&mut self) {
-        for (_, player) in self.players.iter_mut() {
-            player.mods.clear_season();
+    //for catching a bad save or a hand-built world before the sim trips over it.
+    //everything wrong gets listed, not just the first thing
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        for team in self.teams.values() {
+            for &id in team.lineup.iter().chain(team.rotation.iter()).chain(team.shadows.iter()) {
+                if !self.players.contains_key(&id) {
+                    problems.push(format!("{} has {} on the roster, but there's no such player", team.name, id));
+                }
+            }
+        }
+        for &id in self.hall.iter() {
+            if !self.players.contains_key(&id) {
+                problems.push(format!("{} is in the hall, but there's no such player", id));
+            }
+        }
+        for (&id, player) in self.players.iter() {
+            if let Some(team_id) = player.team {
+                match self.teams.get(&team_id) {
+                    None => problems.push(format!("{} ({}) is on team {}, which doesn't exist", player.name, id, team_id)),
+                    Some(team) => {
+                        let listed = team.lineup.contains(&id) || team.rotation.contains(&id) || team.shadows.contains(&id);
+                        if !listed {
+                            problems.push(format!("{} ({}) is on {}, but {} doesn't list them", player.name, id, team.name, team.name));
+                        }
+                    }
+                }
+            }
+            if player.mods.has(Mod::Scattered) && player.scattered_letters == 0 {
+                problems.push(format!("{} ({}) is scattered with no letters left", player.name, id));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
         }
     }
 }
@@ -194,8 +360,8 @@ impl<'a> NameGen<'a> {
         }
     }
     pub fn generate(&self, rng: &mut Rng) -> String {
-        let first_name_index = (rng.next() * self.first_name_length as f64).floor() as usize;
-        let last_name_index = (rng.next() * self.last_name_length as f64).floor() as usize;
+        let first_name_index = rng.index(self.first_name_length as usize);
+        let last_name_index = rng.index(self.last_name_length as usize);
         let mut name = self.first_names[first_name_index].to_string();
         name.push_str(" ");
         name.push_str(self.last_names[last_name_index]);
@@ -270,6 +436,29 @@ impl PlayerAttr {
     }
 }
 
+//the groups stats get boosted and drained in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatCategory {
+    Batting,
+    Pitching,
+    Baserunning,
+    Defense,
+    Vibes,
+}
+
+impl StatCategory {
+    //which indices of a boost vector (see Player::boost) belong to this category
+    pub fn boost_range(&self) -> Range<usize> {
+        match *self {
+            StatCategory::Batting => 0..8,
+            StatCategory::Pitching => 8..14,
+            StatCategory::Baserunning => 14..19,
+            StatCategory::Defense => 19..24,
+            StatCategory::Vibes => 24..26,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Player {
     pub id: Uuid,
@@ -319,8 +508,9 @@ pub struct Player {
 
 impl Player {
     pub fn new(rng: &mut Rng) -> Player {
-        let id = Uuid::new_v4();
-
+        Player::new_with_id(Uuid::new_v4(), rng)
+    }
+    pub fn new_with_id(id: Uuid, rng: &mut Rng) -> Player {
         Player {
             id,
             name: "".to_string(), //todo: name gen
@@ -361,6 +551,18 @@ impl Player {
             cinnamon: rng.next(),
         }
     }
+    //a copy with the same name, stats and mods that's its own player,
+    //for replacements and testing chains. the new id comes out of the rng (two rolls)
+    //so a seeded run gets the same one every time.
+    //it isn't on a team yet, has an empty feed, and the legendary item stays with the original
+    pub fn clone_with_new_id(&self, rng: &mut Rng) -> Player {
+        let mut player = self.clone();
+        player.id = rng_id(rng);
+        player.team = None;
+        player.feed = Events::new();
+        player.remove_legendary_item();
+        player
+    }
     pub fn vibes(&self, day: usize) -> f64 {
         if self.scattered_letters > 0 {
             0.0
@@ -408,6 +610,27 @@ impl Player {
             self.cinnamon += boosts[25];
         }
     }
+    //boosts every stat in a category by the same amount.
+    //negative stats (patheticism, tragicness) go down, same as boost
+    pub fn boost_category(&mut self, category: StatCategory, amount: f64) {
+        let mut boosts: Vec<f64> = vec![0.0; 26];
+        for i in category.boost_range() {
+            boosts[i] = amount;
+        }
+        self.boost(&boosts);
+    }
+    pub fn batting(&self) -> f64 {
+        self.player_rating(0)
+    }
+    pub fn pitching(&self) -> f64 {
+        self.player_rating(1)
+    }
+    pub fn defense(&self) -> f64 {
+        self.player_rating(2)
+    }
+    pub fn baserunning(&self) -> f64 {
+        self.player_rating(3)
+    }
     pub fn player_rating(&self, category: u8) -> f64 {
         let stats_and_pows: Vec<(f64, f64)> = match category {
             //batting
@@ -463,9 +686,9 @@ impl Player {
     }
     pub fn get_run_value(&self) -> f64 {
         if self.mods.has(Mod::Wired) {
-            0.5
+            WIRED_BONUS
         } else if self.mods.has(Mod::Tired) {
-            -0.5
+            TIRED_PENALTY
         } else {
             0.0
         }
@@ -486,6 +709,14 @@ pub enum LegendaryItem {
     ActualAirplane
 }
 
+//a team's regular season at a glance, see World::standings
+#[derive(Clone, Copy, Debug)]
+pub struct Record {
+    pub wins: i16,
+    pub losses: i16,
+    pub run_differential: f64,
+}
+
 #[derive(Clone, Debug)]
 pub struct Team {
     pub id: Uuid,
@@ -500,13 +731,83 @@ pub struct Team {
     pub losses: i16,
     pub postseason_wins: i16,
     pub postseason_losses: i16,
+    pub runs_for: f64, //regular season only, same as wins and losses
+    pub runs_against: f64,
     pub partying: bool,
     pub fate: usize,
+    pub blocked_drains: u16, //drains sealant stopped. sealant is permanent, it doesn't get used up
 
     pub mods: Mods,
 }
 
 impl Team {
+    //nobody on it yet
+    pub fn new(id: Uuid, name: String, emoji: String) -> Team {
+        Team {
+            id,
+            emoji,
+            lineup: Vec::new(),
+            rotation: Vec::new(),
+            shadows: Vec::new(),
+            name,
+            wins: 0,
+            losses: 0,
+            postseason_wins: 0,
+            postseason_losses: 0,
+            runs_for: 0.0,
+            runs_against: 0.0,
+            partying: false,
+            fate: 100,
+            blocked_drains: 0,
+            mods: Mods::new(),
+        }
+    }
+
+    //a copy of the team with a clean record, for running simulated seasons.
+    //everything on Team is owned so the clone doesn't share anything with the original
+    pub fn clone_for_sim(&self) -> Team {
+        let mut team = self.clone();
+        team.reset_records();
+        team
+    }
+
+    pub fn reset_records(&mut self) {
+        self.wins = 0;
+        self.losses = 0;
+        self.postseason_wins = 0;
+        self.postseason_losses = 0;
+        self.runs_for = 0.0;
+        self.runs_against = 0.0;
+    }
+
+    pub fn run_differential(&self) -> f64 {
+        self.runs_for - self.runs_against
+    }
+
+    pub fn record(&self) -> Record {
+        Record {
+            wins: self.wins,
+            losses: self.losses,
+            run_differential: self.run_differential(),
+        }
+    }
+
+    //a team parties once it's more than `margin` losses behind every team in playoff position
+    //(their standings, World::standings has the records), usually margin is the games left
+    //so that means eliminated. returns true if they just started partying
+    pub fn update_partying(&mut self, playoff_standings: &[(Uuid, Record)], margin: i16) -> bool {
+        let playoff_losses = match playoff_standings.iter().map(|(_, record)| record.losses).max() {
+            Some(losses) => losses,
+            None => return false,
+        };
+        if !self.partying && self.losses - playoff_losses > margin {
+            self.partying = true;
+            true
+        } else {
+            false
+        }
+    }
+
     fn replace_player(&mut self, id: Uuid, new_id: Uuid) {
         //todo: write this code with return
         if let Some(idx) = self.lineup.iter().position(|x| *x == id) {
@@ -520,15 +821,15 @@ impl Team {
         }
     }
 
-    //if reverb type is 1 (partial), returns pairs of players to be swapped
+    //if reverb type is Several, returns pairs of players to be swapped
     //if not, returns indexes of old slots (lineup lower) in rotation-lineup order
-    pub fn roll_reverb_changes(&self, rng: &mut Rng, reverb_type: u8, gravity_players: &Vec<usize>) -> Vec<usize> {
+    pub fn roll_reverb_changes(&self, rng: &mut Rng, reverb_type: ReverbType, gravity_players: &[usize]) -> Vec<usize> {
         let mut reverb_changes = Vec::new();
         let lineup_length = self.lineup.len();
         let rotation_length = self.rotation.len();
         let length = lineup_length + rotation_length;
         match reverb_type {
-            0 => {
+            ReverbType::Full => {
                 let mut players_rem: Vec<usize> = Vec::new(); //tracks players still unsorted
                 for i in 0..length {
                     if !gravity_players.contains(&(i as usize)) {
@@ -545,7 +846,7 @@ impl Team {
                             reverb_changes.push(i - lineup_length);
                         }
                     } else {
-                        let rem_idx = (rng.next() * (players_rem.len() as f64)).floor() as usize;
+                        let rem_idx = rng.index(players_rem.len());
                         let idx = players_rem[rem_idx];
                         players_rem.retain(|j| *j != idx);
                         reverb_changes.push(idx);
@@ -553,10 +854,10 @@ impl Team {
                 }
             },
             //everything regarding gravity past this line is an assumption
-            1 => {
+            ReverbType::Several => {
                 for _ in 0..3 {
-                    let roll1 = (rng.next() * (length as f64)).floor() as usize;
-                    let roll2 = (rng.next() * (length as f64)).floor() as usize;
+                    let roll1 = rng.index(length);
+                    let roll2 = rng.index(length);
                     let idx1 = if roll1 < rotation_length { lineup_length + roll1 } else { roll1 - rotation_length };
                     let idx2 = if roll2 < rotation_length { lineup_length + roll2 } else { roll2 - rotation_length };
                     if !gravity_players.contains(&idx1) && !gravity_players.contains(&idx2) {
@@ -565,7 +866,7 @@ impl Team {
                     }
                 }
             },
-            2 => {
+            ReverbType::Lineup => {
                 let mut players_rem: Vec<usize> = Vec::new();
                 for i in 0..lineup_length {
                     if !gravity_players.contains(&(i as usize)) {
@@ -577,14 +878,14 @@ impl Team {
                     if gravity_players.contains(&(i as usize)) {
                         reverb_changes.push(i);
                     } else {
-                        let rem_idx = (rng.next() * (players_rem.len() as f64)).floor() as usize;
+                        let rem_idx = rng.index(players_rem.len());
                         let idx = players_rem[rem_idx];
                         players_rem.retain(|j| *j != idx);
                         reverb_changes.push(idx);
                     }
                 }
             },
-            3 => {
+            ReverbType::Rotation => {
                 let mut players_rem: Vec<usize> = Vec::new();
                 for i in 0..rotation_length {
                     if !gravity_players.contains(&(i + lineup_length)) {
@@ -596,27 +897,24 @@ impl Team {
                     if gravity_players.contains(&((i + lineup_length) as usize)) {
                         reverb_changes.push(i);
                     } else {
-                        let rem_idx = (rng.next() * (players_rem.len() as f64)).floor() as usize;
+                        let rem_idx = rng.index(players_rem.len());
                         let idx = players_rem[rem_idx];
                         players_rem.retain(|j| *j != idx);
                         reverb_changes.push(idx);
                     }
                 }
             },
-            _ => {
-                panic!("wrong reverb type");
-            }
         }
         reverb_changes
     }
 
-    pub fn apply_reverb_changes(&mut self, reverb_type: u8, changes: &Vec<usize>) {
+    pub fn apply_reverb_changes(&mut self, reverb_type: ReverbType, changes: &[usize]) {
         let mut result: Vec<Uuid> = Vec::new();
         let lineup_length = self.lineup.len();
         let rotation_length = self.rotation.len();
         let length = lineup_length + rotation_length;
         match reverb_type {
-            0 => {
+            ReverbType::Full => {
                 for i in rotation_length..length {
                     let player_slot = changes[i];
                     if player_slot < lineup_length {
@@ -634,7 +932,7 @@ impl Team {
                     }
                 }
             },
-            1 => {
+            ReverbType::Several => {
                 for i in 0..rotation_length {
                     result.push(self.rotation[i].clone());
                 }
@@ -649,7 +947,7 @@ impl Team {
                     change_idx += 2;
                 }
             },
-            2 => {
+            ReverbType::Lineup => {
                 for i in 0..lineup_length {
                     result.push(self.lineup[changes[i]].clone());
                 }
@@ -657,7 +955,7 @@ impl Team {
                     result.push(self.rotation[i].clone());
                 }
             },
-            3 => {
+            ReverbType::Rotation => {
                 for i in 0..lineup_length {
                     result.push(self.lineup[i].clone());
                 }
@@ -665,9 +963,6 @@ impl Team {
                     result.push(self.rotation[changes[i]].clone());
                 }
             },
-            _ => {
-                panic!("wrong reverb type, somehow");
-            }
         }
         for i in 0..length {
             if i < lineup_length {
@@ -679,6 +974,30 @@ impl Team {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReverbType {
+    Full, //lineup and rotation shuffled together
+    Several, //a few pairs of players swapped anywhere on the roster
+    Lineup, //lineup shuffled
+    Rotation, //rotation shuffled
+}
+
+impl ReverbType {
+    pub fn affects_lineup(&self) -> bool {
+        match *self {
+            ReverbType::Full | ReverbType::Several | ReverbType::Lineup => true,
+            ReverbType::Rotation => false,
+        }
+    }
+
+    pub fn affects_rotation(&self) -> bool {
+        match *self {
+            ReverbType::Full | ReverbType::Several | ReverbType::Rotation => true,
+            ReverbType::Lineup => false,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Stadium {
     pub id: Uuid,
@@ -686,3 +1005,277 @@ pub struct Stadium {
     pub name: String,
     // todo: stats ig
 }
+
+//a v4 uuid out of two rolls, so seeded runs get the same one every time
+fn rng_id(rng: &mut Rng) -> Uuid {
+    let mut bytes = [0u8; 16];
+    for half in bytes.chunks_mut(8) {
+        half.copy_from_slice(&((rng.next() * u64::MAX as f64) as u64).to_le_bytes());
+    }
+    uuid::Builder::from_random_bytes(bytes).into_uuid()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::test_world;
+
+    #[test]
+    fn advance_day_clears_game_mods() {
+        let (mut world, _, _, away) = test_world(12);
+        let player = world.team(away).lineup[0];
+        world.give_mod(player, Mod::Wired, ModLifetime::Game);
+        world.give_mod(player, Mod::Superallergic, ModLifetime::Week);
+        let expired = world.advance_day();
+        assert_eq!(expired, vec![(player, Mod::Wired)]);
+        assert!(world.player(player).mods.has(Mod::Superallergic));
+        assert_eq!(world.day, 1);
+    }
+
+    #[test]
+    fn advance_day_clears_weekly_mods_at_the_end_of_the_week() {
+        let (mut world, _, _, away) = test_world(12);
+        let player = world.team(away).lineup[0];
+        world.give_mod(player, Mod::Superallergic, ModLifetime::Week);
+        for _ in 0..DAYS_PER_WEEK - 1 {
+            world.advance_day();
+        }
+        assert!(world.player(player).mods.has(Mod::Superallergic));
+        world.advance_day();
+        assert!(!world.player(player).mods.has(Mod::Superallergic));
+        assert_eq!(world.day, DAYS_PER_WEEK);
+    }
+
+    #[test]
+    fn update_partying_from_standings() {
+        let (mut world, _, home, away) = test_world(12);
+        world.team_mut(home).wins = 60;
+        world.team_mut(home).losses = 20;
+        world.team_mut(away).wins = 20;
+        world.team_mut(away).losses = 60;
+        let playoff_standings: Vec<(Uuid, Record)> = world.standings().into_iter().take(1).collect();
+        assert_eq!(playoff_standings[0].0, home);
+
+        //19 games left, 40 back
+        assert!(world.team_mut(away).update_partying(&playoff_standings, 19));
+        assert!(world.team(away).partying);
+        //only says so the first time
+        assert!(!world.team_mut(away).update_partying(&playoff_standings, 19));
+
+        assert!(!world.team_mut(home).update_partying(&playoff_standings, 19));
+        assert!(!world.team(home).partying);
+    }
+
+    #[test]
+    fn update_partying_in_contention() {
+        let (mut world, _, home, away) = test_world(12);
+        world.team_mut(home).losses = 20;
+        world.team_mut(away).losses = 30;
+        let playoff_standings = vec![(home, world.team(home).record())];
+        assert!(!world.team_mut(away).update_partying(&playoff_standings, 19));
+        assert!(!world.team(away).partying);
+    }
+
+    #[test]
+    fn clone_with_new_id_keeps_stats() {
+        let mut rng = Rng::new(69, 420);
+        let player = Player::new(&mut rng);
+        let clone = player.clone_with_new_id(&mut rng);
+        assert_ne!(clone.id, player.id);
+        assert_eq!(clone.name, player.name);
+        for category in 0..4 {
+            assert_eq!(clone.player_rating(category), player.player_rating(category));
+        }
+        assert_eq!((clone.thwackability, clone.ruthlessness, clone.omniscience), (player.thwackability, player.ruthlessness, player.omniscience));
+
+        //and the next one's different again
+        assert_ne!(player.clone_with_new_id(&mut rng).id, clone.id);
+    }
+
+    #[test]
+    fn player_or_team_has_either() {
+        let (mut world, _, home, away) = test_world(12);
+        let player = world.team(away).lineup[0];
+        assert!(!world.player_or_team_has(player, away, Mod::Fireproof));
+        world.team_mut(away).mods.add(Mod::Fireproof, ModLifetime::Permanent);
+        assert!(world.player_or_team_has(player, away, Mod::Fireproof));
+        //whoever they're playing for, not their team field
+        assert!(!world.player_or_team_has(player, home, Mod::Fireproof));
+        world.give_mod(player, Mod::Fireproof, ModLifetime::Game);
+        assert!(world.player_or_team_has(player, home, Mod::Fireproof));
+    }
+
+    //same seed, same ids, down to the incineration replacements
+    #[test]
+    fn deterministic_ids_reproduce() {
+        let run = || {
+            let mut rng = Rng::new(69, 420);
+            let mut world = World::new(12);
+            world.deterministic_ids();
+            let team = world.gen_team(&mut rng, String::from("Team"), String::from("T"));
+            let replacement = world.new_id(&mut rng);
+            let mut ids: Vec<Uuid> = world.players.keys().copied().collect();
+            ids.push(team);
+            ids.push(replacement);
+            ids
+        };
+        let ids = run();
+        assert_eq!(ids.len(), 27);
+        assert_eq!(ids, run());
+    }
+
+    //two games on one day, every team comes out with one decision
+    #[test]
+    fn play_day_records_results() {
+        let (mut world, mut rng, home1, away1) = test_world(12);
+        let home2 = world.gen_team(&mut rng, String::from("Home 2"), String::from("H"));
+        let away2 = world.gen_team(&mut rng, String::from("Away 2"), String::from("A"));
+        let mut schedule = Schedule::new(0);
+        schedule.add(home1, away1, Some(crate::Weather::Sun));
+        schedule.add(home2, away2, Some(crate::Weather::Sun));
+
+        let games = world.play_day(&schedule, &mut rng);
+        assert_eq!(games.len(), 2);
+        for game in games.iter() {
+            let result = game.result().unwrap();
+            assert_eq!(world.team(result.winning_team).wins, 1);
+            assert_eq!(world.team(result.winning_team).losses, 0);
+            assert_eq!(world.team(result.losing_team).wins, 0);
+            assert_eq!(world.team(result.losing_team).losses, 1);
+        }
+        for team in [home1, away1, home2, away2] {
+            assert_eq!(world.team(team).wins + world.team(team).losses, 1);
+        }
+    }
+
+    //expired mods come back by player id, then by where the mod is in the Mod enum,
+    //whatever order they went on in
+    #[test]
+    fn clear_game_expiry_order() {
+        let (mut world, _, home, _) = test_world(12);
+        let mut players = world.team(home).lineup[..2].to_vec();
+        players.sort();
+        world.give_mod(players[1], Mod::Fireproof, ModLifetime::Game);
+        world.give_mod(players[0], Mod::Unstable, ModLifetime::Game);
+        world.give_mod(players[0], Mod::Flinch, ModLifetime::Game);
+        world.give_mod(players[0], Mod::Soundproof, ModLifetime::Week);
+
+        assert_eq!(world.clear_game(), vec![
+            (players[0], Mod::Flinch),
+            (players[0], Mod::Unstable),
+            (players[1], Mod::Fireproof),
+        ]);
+        assert!(world.clear_game().is_empty());
+        assert!(world.player(players[0]).mods.has(Mod::Soundproof));
+    }
+
+    //every stat, in boost vector order
+    fn stats(player: &Player) -> Vec<f64> {
+        vec![
+            player.buoyancy, player.divinity, player.martyrdom, player.moxie, player.musclitude, player.patheticism, player.thwackability, player.tragicness,
+            player.coldness, player.overpowerment, player.ruthlessness, player.shakespearianism, player.suppression, player.unthwackability,
+            player.base_thirst, player.continuation, player.ground_friction, player.indulgence, player.laserlikeness,
+            player.anticapitalism, player.chasiness, player.omniscience, player.tenaciousness, player.watchfulness,
+            player.pressurization, player.cinnamon,
+        ]
+    }
+
+    #[test]
+    fn boost_category_only_touches_its_stats() {
+        let mut rng = Rng::new(69, 420);
+        let mut player = Player::new(&mut rng);
+        let before = stats(&player);
+        player.boost_category(StatCategory::Pitching, 0.1);
+        let after = stats(&player);
+        for i in 0..26 {
+            let expected = if StatCategory::Pitching.boost_range().contains(&i) { before[i] + 0.1 } else { before[i] };
+            assert_eq!(after[i], expected, "stat {}", i);
+        }
+
+        //batting's negative stats go the other way
+        player.boost_category(StatCategory::Batting, 0.1);
+        let batted = stats(&player);
+        assert_eq!(batted[5], after[5] - 0.1);
+        assert_eq!(batted[6], after[6] + 0.1);
+    }
+
+    #[test]
+    fn clone_for_sim_is_detached() {
+        let (mut world, _, home, _) = test_world(12);
+        world.team_mut(home).wins = 5;
+        world.team_mut(home).postseason_losses = 2;
+        world.team_mut(home).runs_for = 30.0;
+        let original = world.team(home);
+
+        let mut clone = original.clone_for_sim();
+        assert_eq!((clone.wins, clone.losses, clone.postseason_wins, clone.postseason_losses), (0, 0, 0, 0));
+        assert_eq!((clone.runs_for, clone.runs_against), (0.0, 0.0));
+        assert_eq!(clone.lineup, original.lineup);
+
+        clone.wins += 3;
+        clone.losses += 1;
+        clone.mods.add(Mod::Sealant, ModLifetime::Permanent);
+        clone.lineup.swap(0, 1);
+        assert_eq!((original.wins, original.losses, original.postseason_losses, original.runs_for), (5, 0, 2, 30.0));
+        assert!(!original.mods.has(Mod::Sealant));
+        assert_ne!(clone.lineup, original.lineup);
+
+        clone.reset_records();
+        assert_eq!((clone.wins, clone.losses), (0, 0));
+    }
+
+
+    #[test]
+    fn validate_catches_a_dangling_roster_spot() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        assert_eq!(world.validate(), Ok(()));
+
+        let ghost = world.new_id(&mut rng);
+        world.team_mut(home).lineup.push(ghost);
+        let problems = world.validate().unwrap_err();
+        assert_eq!(problems, vec![format!("{} has {} on the roster, but there's no such player", world.team(home).name, ghost)]);
+
+        //and everything else wrong gets listed along with it
+        let leaver = world.team_mut(away).lineup.remove(0);
+        world.player_mut(leaver).mods.add(Mod::Scattered, ModLifetime::Permanent);
+        world.player_mut(leaver).scattered_letters = 0;
+        assert_eq!(world.validate().unwrap_err().len(), 3);
+    }
+
+
+    //three teams, two tied on wins: run differential sorts those two, fate only if that's tied too
+    #[test]
+    fn standings_tiebreakers() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let third = world.gen_team(&mut rng, String::from("Third"), String::from("T"));
+        for (id, wins, runs_for, fate) in [(home, 10, 40.0, 0), (away, 10, 50.0, 1), (third, 12, 10.0, 2)] {
+            let team = world.team_mut(id);
+            team.wins = wins;
+            team.runs_for = runs_for;
+            team.runs_against = 45.0;
+            team.fate = fate;
+        }
+        let order = |world: &World| world.standings().iter().map(|&(id, _)| id).collect::<Vec<_>>();
+        assert_eq!(order(&world), vec![third, away, home]);
+        assert_eq!(world.standings()[1].1.run_differential, 5.0);
+
+        world.team_mut(home).runs_for = 50.0;
+        world.team_mut(home).fate = 5;
+        assert_eq!(order(&world), vec![third, away, home]);
+        world.team_mut(home).fate = 0;
+        assert_eq!(order(&world), vec![third, home, away]);
+    }
+
+
+    #[test]
+    fn try_player_on_an_unknown_id() {
+        let (mut world, _, home, _) = test_world(12);
+        let known = world.team(home).lineup[0];
+        let unknown = Uuid::new_v4();
+        assert!(world.try_player(unknown).is_none());
+        assert!(world.try_player_mut(unknown).is_none());
+        assert_eq!(world.try_player(known).map(|p| p.id), Some(known));
+        world.try_player_mut(known).unwrap().name = String::from("Known");
+        assert_eq!(world.player(known).name, "Known");
+    }
+}
@@ -1,8 +1,23 @@
 use uuid::Uuid;
 use strum::Display;
-use std::string::ToString;
+use std::{cell::Cell, cmp::Ordering, string::ToString, time::Duration};
+use log::{debug, info};
 
-use crate::{bases::Baserunners, entities::{Player, World}, mods::{Mod, ModLifetime}, Game, Weather};
+use crate::{bases::Baserunners, entities::{Player, ReverbType, StatCategory, World}, mods::{self, Mod, ModLifetime, BLASERUNNING_BONUS, FLIPPERS_BONUS, TRIPLE_THREAT_PENALTY}, Game};
+
+thread_local! {
+    //set while Event::simulate is applying to its copies, so previews don't end up in the log
+    static PREVIEWING: Cell<bool> = const { Cell::new(false) };
+}
+
+//info!/debug! for apply, quiet during a preview
+macro_rules! apply_log {
+    ($level:ident, $($arg:tt)*) => {
+        if !PREVIEWING.with(|p| p.get()) {
+            $level!($($arg)*);
+        }
+    };
+}
 
 #[derive(Display, Debug, Clone)]
 pub enum Event {
@@ -12,6 +27,7 @@ pub enum Event {
     InningSwitch {
         inning: i16,
         top: bool,
+        runs_this_half: f64, //runs the half-inning that just ended put up
     },
     GameOver,
 
@@ -21,6 +37,7 @@ pub enum Event {
 
     Strikeout,
     Walk,
+    IntentionalWalk, //not from the sim, see Game::intentional_walk
     HomeRun,
 
     // todo: find a nicer way to encode runner advancement
@@ -72,14 +89,14 @@ pub enum Event {
         target2: Uuid,
     },
     Reverb {
-        reverb_type: u8,
+        reverb_type: ReverbType,
         team: Uuid,
         changes: Vec<usize>
     },
     Blooddrain {
         drainer: Uuid,
         target: Uuid,
-        stat: u8,
+        stat: StatCategory,
         siphon: bool,
         siphon_effect: i16
     },
@@ -109,6 +126,11 @@ pub enum Event {
         tangled: Uuid,
         decreases: Vec<f64>
     },
+    //both feedback targets soundproof: they both resist, nobody swaps and nobody gets tangled
+    BothSoundproof {
+        target1: Uuid,
+        target2: Uuid,
+    },
     Reverberating {
         batter: Uuid,
     },
@@ -158,8 +180,8 @@ pub enum Event {
         target: Uuid,
     },
     Performing {
-        overperforming: Vec<Uuid>,
-        underperforming: Vec<Uuid>,
+        overperforming: Vec<(Uuid, Mod)>, //player, the mod that made them perform
+        underperforming: Vec<(Uuid, Mod)>,
     },
     Beaned,
     PouredOver,
@@ -204,43 +226,56 @@ pub enum Event {
 impl Event {
     pub fn apply(&self, game: &mut Game, world: &mut World) {
         let repr = self.repr();
-        if let Event::BatterUp { .. } = self {
-            assert_eq!(repr, String::from("BatterUp"));
-        }
         game.events.add(repr.clone());
         match *self {
             Event::BatterUp { batter } => {
                 //println!("{:?}", world.player(batter).mods);
                 let bt = game.scoreboard.batting_team_mut();
                 bt.batter = Some(batter);
-                if !game.started { game.started = true };
-            }
-            Event::InningSwitch { inning, top } => {
-                if let Weather::Salmon = game.weather {
-                    if game.scoreboard.top {
-                        let runs_away = game.scoreboard.away_team.score - game.linescore_away[0];
-                        game.linescore_away.push(runs_away);
-                        game.linescore_away[0] += runs_away;
-                    } else {
-                        let runs_home = game.scoreboard.home_team.score - game.linescore_home[0];
-                        game.linescore_home.push(runs_home);
-                        game.linescore_home[0] += runs_home;
+                if !game.started {
+                    game.started = true;
+                    for team in [game.scoreboard.home_team.id, game.scoreboard.away_team.id] {
+                        let team = world.team(team);
+                        let players: Vec<Uuid> = team.lineup.iter().chain(team.rotation.iter()).copied().collect();
+                        for player in players {
+                            world.player_mut(player).feed.start_game();
+                        }
                     }
                 }
+            }
+            Event::InningSwitch { inning, top, runs_this_half } => {
+                if game.scoreboard.top {
+                    game.linescore_away.push(runs_this_half);
+                    game.linescore_away[0] += runs_this_half;
+                } else {
+                    game.linescore_home.push(runs_this_half);
+                    game.linescore_home[0] += runs_this_half;
+                }
                 game.inning = inning;
                 game.scoreboard.top = top;
                 game.outs = 0;
                 game.balls = 0;
                 game.strikes = 0;
                 game.scoring_plays_inning = 0;
+                game.reverberations_inning = 0;
+                //sides just flipped, so this is the new batting team's base count
                 game.runners = Baserunners::new(game.get_bases(world));
             }
             Event::GameOver => {
-                let winning_team = if game.scoreboard.home_team.score > game.scoreboard.away_team.score { game.scoreboard.home_team.id } else { game.scoreboard.away_team.id };
-                let losing_team = if game.scoreboard.home_team.score > game.scoreboard.away_team.score { game.scoreboard.away_team.id } else { game.scoreboard.home_team.id };
+                let home_won = game.lead() == Ordering::Greater;
+                let winning_team = if home_won { game.scoreboard.home_team.id } else { game.scoreboard.away_team.id };
+                let losing_team = if home_won { game.scoreboard.away_team.id } else { game.scoreboard.home_team.id };
                 if game.day < 99 {
                     world.team_mut(winning_team).wins += 1;
                     world.team_mut(losing_team).losses += 1;
+                    let home_score = game.scoreboard.home_team.score;
+                    let away_score = game.scoreboard.away_team.score;
+                    let home = world.team_mut(game.scoreboard.home_team.id);
+                    home.runs_for += home_score;
+                    home.runs_against += away_score;
+                    let away = world.team_mut(game.scoreboard.away_team.id);
+                    away.runs_for += away_score;
+                    away.runs_against += home_score;
                 } else {
                     world.team_mut(winning_team).postseason_wins += 1;
                     world.team_mut(losing_team).postseason_losses += 1;
@@ -253,28 +288,27 @@ impl Event {
                 game.strikes += 1;
             }
             Event::Foul => {
+                game.fouls += 1;
                 game.strikes += 1;
                 game.strikes = game.strikes.min(game.get_max_strikes(world) - 1);
             }
             Event::Strikeout | Event::CharmStrikeout => {
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
-                let triple_threat_active = world.player(game.pitcher()).mods.has(Mod::TripleThreat)
-                    && (game.balls == 3
-                        || game.runners.occupied(2)
-                        || game.runners.len() == 3);
-                if triple_threat_active {
-                    game.scoreboard.batting_team_mut().score -= 0.3;
+                if game.triple_threat_active(world) && world.ruleset.triple_threat_penalty {
+                    game.scoreboard.batting_team_mut().score += TRIPLE_THREAT_PENALTY;
                 }
+                game.credit_strikeout();
                 game.outs += 1;
                 game.end_pa();
             }
-            Event::Walk | Event::CharmWalk => {
+            Event::Walk | Event::CharmWalk | Event::IntentionalWalk => {
                 // maybe we should put batter in the event
                 // todo: make a function that returns the current batter
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 game.runners.walk();
                 game.runners.add(0, game.batter().unwrap());
-                game.score(world);
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             }
@@ -283,69 +317,82 @@ impl Event {
                 upgrade_spicy(game, world);
                 let no_runners_on = game.runners.empty();
                 game.runners.advance_all(game.get_bases(world));
-                game.score(world);
-                game.scoreboard.batting_team_mut().score += game.get_run_value();
+                let rbis = game.score(world);
+                game.credit_rbis(rbis + 1); //the batter brings themselves in
+                game.charge_runs(1);
+                game.scoreboard.batting_team_mut().score += game.get_run_value(world);
                 game.scoreboard.batting_team_mut().score += world.player(game.batter().unwrap()).get_run_value();
                 game.base_sweep();
                 if no_runners_on {
                     game.scoring_plays_inning += 1;
-                } //this is to make sum sun not break
+                } //base_sweep only counts runners, see Game::scoring_plays_inning
                 game.end_pa();
             }
             Event::BaseHit {
                 bases,
                 ref runners_after,
             } => {
+                check_runners_after(game, runners_after);
                 let batter = game.batter().unwrap();
                 world.player_mut(batter).feed.add(repr.clone());
                 upgrade_spicy(game, world);
-                game.runners = runners_after.clone();
-                game.score(world);
+                game.runners = *runners_after;
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.runners
                     .add(bases - 1, batter);
                 game.end_pa();
             }
             Event::GroundOut {
-                fielder: _fielder,
+                fielder,
                 ref runners_after,
             } => {
+                check_runners_after(game, runners_after);
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 downgrade_spicy(game, world);
+                game.credit_putout(fielder);
                 game.outs += 1;
-                game.runners = runners_after.clone();
-                game.score(world);
+                game.runners = *runners_after;
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             }
             Event::Flyout {
-                fielder: _fielder,
+                fielder,
                 ref runners_after,
             } => {
+                check_runners_after(game, runners_after);
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 downgrade_spicy(game, world);
+                game.credit_putout(fielder);
                 game.outs += 1;
-                game.runners = runners_after.clone();
-                game.score(world);
+                game.runners = *runners_after;
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             }
             Event::DoublePlay { ref runners_after } => {
+                check_runners_after(game, runners_after);
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 downgrade_spicy(game, world);
                 game.outs += 2;
-                game.runners = runners_after.clone();
+                game.runners = *runners_after;
                 game.score(world);
                 game.base_sweep();
                 game.end_pa();
             }
             Event::FieldersChoice { ref runners_after } => {
+                check_runners_after(game, runners_after);
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 downgrade_spicy(game, world);
                 game.outs += 1;
-                game.runners = runners_after.clone();
+                game.runners = *runners_after;
                 game.runners.add(0, game.batter().unwrap());
-                game.score(world);
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             }
@@ -355,7 +402,7 @@ impl Event {
                 base_to: _base_to,
             } => {
                 if world.player(runner).mods.has(Mod::Blaserunning) {
-                    game.scoreboard.batting_team_mut().score += 0.2;
+                    game.scoreboard.batting_team_mut().score += BLASERUNNING_BONUS;
                 }
                 game.runners.advance(base_from);
                 game.score(world);
@@ -375,12 +422,14 @@ impl Event {
                 world.player_mut(target).boost(boosts);
             },
             Event::Incineration { target, ref replacement, chain, ambush } => {
-                println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                println!("Incineration: {}", world.player(target).name);
-                println!("Team: {}", world.team(world.player(target).team.unwrap()).name);
-                let new_player = replacement.name == "";
-                let replacement_id = if new_player {
-                    world.add_rolled_player(replacement.clone(), world.player(target).team.unwrap())
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Incineration: {}", world.player(target).name);
+                apply_log!(info, "Team: {}", world.team(world.player(target).team.unwrap()).name);
+                let team = world.player(target).team.unwrap();
+                let drawn = world.draw_from_pool(replacement.id, team);
+                let new_player = drawn || replacement.name.is_empty();
+                let replacement_id = if new_player && !drawn {
+                    world.add_rolled_player(replacement.clone(), team)
                 } else {
                     replacement.id
                 };
@@ -393,6 +442,8 @@ impl Event {
                 } else if target == game.scoreboard.batting_team().pitcher {
                     game.scoreboard.batting_team_mut().pitcher = replacement_id;
                 }
+                //regular incinerations skip runners but nothing stops other paths from picking one
+                game.runners.replace(target, replacement_id);
                 if new_player {
                     world.replace_player(target, replacement_id);
                 } else {
@@ -415,11 +466,13 @@ impl Event {
                 }
             },
             Event::Peanut { target, yummy } => {
-                println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                println!("Peanut: {}", world.player(target).name);
-                println!("Team: {}", world.team(world.player(target).team.unwrap()).name);
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Peanut: {}", world.player(target).name);
+                apply_log!(info, "Team: {}", world.team(world.player(target).team.unwrap()).name);
                 let coeff = if yummy {
                     0.2
+                } else if world.player(target).mods.has(Mod::Superallergic) {
+                    mods::SUPERALLERGIC_PENALTY
                 } else {
                     -0.2
                 };
@@ -437,8 +490,8 @@ impl Event {
             },
             Event::Birds => {},
             Event::Feedback { target1, target2 } => {
-                println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                println!("Feedback: {}, {}", world.player(target1).name, world.player(target2).name);
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Feedback: {}, {}", world.player(target1).name, world.player(target2).name);
                 if let Some(batter) = game.batter() {
                     if batter == target1 {
                         game.assign_batter(target2);
@@ -452,60 +505,34 @@ impl Event {
                 world.swap(target1, target2);
             },
             Event::Reverb { reverb_type, team, ref changes } => {
-                println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                println!("Reverb");
-                println!("Team: {}", world.team(team).name);
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Reverb");
+                apply_log!(info, "Team: {}", world.team(team).name);
                 world.team_mut(team).apply_reverb_changes(reverb_type, changes);
-                if reverb_type != 3 && game.scoreboard.batting_team().id == team {
+                //whoever ends up in the active slots takes over
+                if reverb_type.affects_lineup() && game.scoreboard.batting_team().id == team {
                     let idx = game.scoreboard.batting_team().batter_index;
                     let world_team = world.team(team);
-                    let new_batter = world_team.lineup[idx % world_team.lineup.len()].clone();
+                    let new_batter = world_team.lineup[idx % world_team.lineup.len()];
                     game.assign_batter(new_batter);
-                } else if reverb_type != 2 {
+                }
+                if reverb_type.affects_rotation() {
+                    let world_team = world.team(team);
+                    let new_pitcher = world_team.rotation[game.day % world_team.rotation.len()];
                     if game.scoreboard.pitching_team().id == team {
-                        game.assign_pitcher(world.team(team).rotation[game.day % world.team(team).rotation.len()].clone());
+                        game.assign_pitcher(new_pitcher);
                     } else {
-                        game.scoreboard.batting_team_mut().pitcher = world.team(team).rotation[game.day % world.team(team).rotation.len()].clone();
+                        game.scoreboard.batting_team_mut().pitcher = new_pitcher;
                     }
                 }
             },
             Event::Blooddrain { drainer, target, stat, siphon: _siphon, siphon_effect } => {
-                println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                println!("Blooddrain: {}, {}", world.player(drainer).name, world.player(target).name);
-                println!("Drainer team: {}", world.team(world.player(drainer).team.unwrap()).name);
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Blooddrain: {}, {}", world.player(drainer).name, world.player(target).name);
+                apply_log!(info, "Drainer team: {}", world.team(world.player(drainer).team.unwrap()).name);
                 match siphon_effect {
                     -1 => {
-                        let drainer_mut = world.player_mut(drainer);
-                        let mut boosts: Vec<f64> = vec![0.0; 26];
-                        match stat {
-                            0 => {
-                                //pitching
-                                for i in 8..14 {
-                                    boosts[i] = 0.1;
-                                }
-                            },
-                            1 => {
-                                //batting
-                                for i in 0..8 {
-                                    boosts[i] = 0.1;
-                                }
-                            },
-                            2 => {
-                                //defense
-                                for i in 19..24 {
-                                    boosts[i] = 0.1;
-                                }
-                            },
-                            3 => {
-                                //baserunning
-                                for i in 14..19 {
-                                    boosts[i] = 0.1;
-                                }
-                            },
-                            _ => {
-                            }
-                        }
-                        drainer_mut.boost(&boosts);
+                        world.player_mut(drainer).boost_category(stat, 0.1);
                     },
                     0 => {
                         game.outs += 1;
@@ -522,32 +549,7 @@ impl Event {
                 }
 
                 let target_mut = world.player_mut(target);
-                let mut decreases: Vec<f64> = vec![0.0; 26];
-                match stat {
-                    0 => {
-                        for i in 8..14 {
-                            decreases[i] = -0.1;
-                        }
-                    },
-                    1 => {
-                        for i in 0..8 {
-                            decreases[i] = -0.1;
-                        }
-                    },
-                    2 => {
-                        for i in 19..24 {
-                            decreases[i] = -0.1;
-                        }
-                    },
-                    3 => {
-                        for i in 14..19 {
-                            decreases[i] = -0.1;
-                        }
-                    },
-                    _ => {
-                    }
-                }
-                target_mut.boost(&decreases);
+                target_mut.boost_category(stat, -0.1);
                 let home = target_mut.team.unwrap() == game.scoreboard.home_team.id;
                 if home {
                     game.home_impaired = true;
@@ -556,9 +558,13 @@ impl Event {
                 }
             },
             //todo: add win manipulation when we actually have wins
+            //both fire on the team that got to 10 (home_team says which side), and both take the 10 runs.
+            //sun 2 gives that team a win. the black hole swallows a win from their opponent instead,
+            //which is what the game did: "the black hole swallowed the runs and a win from the ___"
             Event::Sun2 { home_team } => {
                 if home_team {
                     game.scoreboard.home_team.score -= 10.0;
+                    game.linescore_home[0] -= 10.0; //so runs_this_half still counts the runs that went in
                     if game.day > 98 {
                         world.team_mut(game.scoreboard.home_team.id).postseason_wins += 1;
                     } else {
@@ -566,6 +572,7 @@ impl Event {
                     }
                 } else {
                     game.scoreboard.away_team.score -= 10.0;
+                    game.linescore_away[0] -= 10.0;
                     if game.day > 98 {
                         world.team_mut(game.scoreboard.away_team.id).postseason_wins += 1;
                     } else {
@@ -576,6 +583,8 @@ impl Event {
             Event::BlackHole { home_team, carcinized } => {
                 if home_team {
                     game.scoreboard.home_team.score -= 10.0;
+                    game.linescore_home[0] -= 10.0;
+                    //the other team loses the win, not the one that overflowed
                     if game.day > 98 {
                         world.team_mut(game.scoreboard.away_team.id).postseason_wins -= 1;
                     } else {
@@ -588,6 +597,7 @@ impl Event {
                     }
                 } else {
                     game.scoreboard.away_team.score -= 10.0;
+                    game.linescore_away[0] -= 10.0;
                     if game.day > 98 {
                         world.team_mut(game.scoreboard.home_team.id).postseason_wins -= 1;
                     } else {
@@ -607,10 +617,14 @@ impl Event {
                 if away_runs_lost {
                     //this whole exercise's goal is
                     //to find the first instance of the inning
-                    game.scoreboard.away_team.score -= game.linescore_away[game.linescore_away.len() - 1 - (game.salmon_resets_inning as usize)];
+                    let runs = salmon_runs(&game.linescore_away, game.salmon_resets_inning);
+                    game.scoreboard.away_team.score -= runs;
+                    game.linescore_away[0] -= runs;
                 }
                 if home_runs_lost {
-                    game.scoreboard.home_team.score -= game.linescore_home[game.linescore_home.len() - 1 - (game.salmon_resets_inning as usize)];
+                    let runs = salmon_runs(&game.linescore_home, game.salmon_resets_inning);
+                    game.scoreboard.home_team.score -= runs;
+                    game.linescore_home[0] -= runs;
                 }
                 if !game.scoreboard.top {
                     game.scoreboard.top = true
@@ -645,6 +659,8 @@ impl Event {
             },
             Event::Fireproof { target: _target } | Event::IffeyJr { target: _target } => {},
             Event::Soundproof { resists: _resists, tangled, ref decreases } => {
+                //two soundproof targets is BothSoundproof, nobody soundproof gets tangled
+                debug_assert!(!world.player(tangled).mods.has(Mod::Soundproof), "tangled a soundproof player");
                 world.player_mut(tangled).boost(decreases);
                 let home = world.player(tangled).team.unwrap() == game.scoreboard.home_team.id;
                 if home {
@@ -653,14 +669,17 @@ impl Event {
                     game.away_impaired = true;
                 }
             },
+            Event::BothSoundproof { target1, target2 } => {
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Both soundproof: {}, {}", world.player(target1).name, world.player(target2).name);
+            },
             Event::Reverberating { batter } => {
-                let bt = game.scoreboard.batting_team_mut();
-                bt.batter_index -= 1;
-                bt.batter = Some(batter);
+                game.shift_batter_index(-1);
+                game.scoreboard.batting_team_mut().batter = Some(batter);
+                game.reverberations_inning += 1;
             }
             Event::Shelled { batter: _batter } | Event::Elsewhere { batter: _batter } => {
-                let bt = game.scoreboard.batting_team_mut();
-                bt.batter_index += 1;
+                game.shift_batter_index(1);
                 if !game.started { game.started = true };
             },
             Event::HitByPitch { target, hbp_type } => {
@@ -671,9 +690,11 @@ impl Event {
                     _ => None
                 };
                 world.player_mut(target).mods.add(effect.unwrap(), ModLifetime::Week);
+                //forced like a walk, so with the bases loaded the runner on third comes in
                 game.runners.walk();
                 game.runners.add(0, game.batter().unwrap());
-                game.score(world);
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             },
@@ -692,14 +713,15 @@ impl Event {
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 game.runners.walk_instincts(third);
                 game.runners.add(if third { 2 } else { 1 }, game.batter().unwrap());
-                game.score(world);
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             },
             Event::BigPeanut { target } => {
-                println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                println!("Shelled by big peanut: {}", world.player(target).name);
-                println!("Team: {}", world.team(world.player(target).team.unwrap()).name);
+                apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                apply_log!(info, "Shelled by big peanut: {}", world.player(target).name);
+                apply_log!(info, "Team: {}", world.team(world.player(target).team.unwrap()).name);
                 world.player_mut(target).mods.add(Mod::Shelled, ModLifetime::Permanent);
             },
             Event::MildPitch => {
@@ -712,14 +734,14 @@ impl Event {
                 world.player_mut(game.batter().unwrap()).feed.add(repr.clone());
                 game.runners.advance_all(1);
                 game.runners.add(0, game.batter().unwrap());
-                game.score(world);
+                let rbis = game.score(world);
+                game.credit_rbis(rbis);
                 game.base_sweep();
                 game.end_pa();
             },
             Event::Repeating { batter } => {
-                let bt = game.scoreboard.batting_team_mut();
-                bt.batter_index -= 1;
-                bt.batter = Some(batter);
+                game.shift_batter_index(-1);
+                game.scoreboard.batting_team_mut().batter = Some(batter);
             },
             Event::FireEater { target } => {
                 world.player_mut(target).mods.add(Mod::Magmatic, ModLifetime::Permanent);
@@ -730,33 +752,40 @@ impl Event {
                 upgrade_spicy(game, world);
                 let no_runners_on = game.runners.empty();
                 game.runners.advance_all(game.get_bases(world));
-                game.score(world);
-                game.scoreboard.batting_team_mut().score += game.get_run_value();
+                let rbis = game.score(world);
+                game.credit_rbis(rbis + 1); //the batter brings themselves in
+                game.charge_runs(1);
+                game.scoreboard.batting_team_mut().score += game.get_run_value(world);
                 game.scoreboard.batting_team_mut().score += world.player(game.batter().unwrap()).get_run_value();
                 game.base_sweep();
                 if no_runners_on {
                     game.scoring_plays_inning += 1;
-                } //this is to make sum sun not break
+                } //base_sweep only counts runners, see Game::scoring_plays_inning
                 game.end_pa();
             },
             Event::CrowAmbush => {
+                game.credit_strikeout();
                 game.outs += 1;
                 game.end_pa();
             },
             Event::TasteTheInfinite { target } => {
                 world.player_mut(target).mods.add(Mod::Shelled, ModLifetime::Permanent);
             },
-            Event::Inhabiting { batter: _batter, inhabit } => {
+            Event::Inhabiting { batter, inhabit } => {
                 let bt = game.scoreboard.batting_team_mut();
                 bt.batter = Some(inhabit);
+                bt.inhabited = Some(batter);
                 if !game.started { game.started = true }
             },
-            Event::BlockedDrain { drainer: _drainer, target: _target } => {},
+            Event::BlockedDrain { drainer: _drainer, target } => {
+                let team = world.player(target).team.unwrap();
+                world.team_mut(team).blocked_drains += 1;
+            },
             Event::Performing { ref overperforming, ref underperforming } => {
-                for &player in overperforming {
+                for &(player, _reason) in overperforming {
                     world.player_mut(player).mods.add(Mod::Overperforming, ModLifetime::Game);
                 }
-                for &player in underperforming {
+                for &(player, _reason) in underperforming {
                     world.player_mut(player).mods.add(Mod::Underperforming, ModLifetime::Game);
                 }
             },
@@ -783,32 +812,32 @@ impl Event {
                 if away { world.player_mut(game.scoreboard.away_team.pitcher).mods.remove(Mod::TripleThreat); }
             },
             Event::Swept { ref elsewhere } => {
-                let runners = game.runners.clone();
+                let runners = game.runners;
                 for runner in runners.iter() {
                     if world.player(runner.id).mods.has(Mod::Flippers) {
-                        game.scoreboard.batting_team_mut().score += world.player(runner.id).get_run_value() + 1.0;
+                        game.scoreboard.batting_team_mut().score += world.player(runner.id).get_run_value() + FLIPPERS_BONUS;
                     }
                 }
                 game.runners.clear();
                 for &runner in elsewhere {
-                    println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                    println!("Swept Elsewhere: {}", world.player(runner).name);
-                    println!("Team: {}", world.team(world.player(runner).team.unwrap()).name);
+                    apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                    apply_log!(info, "Swept Elsewhere: {}", world.player(runner).name);
+                    apply_log!(info, "Team: {}", world.team(world.player(runner).team.unwrap()).name);
                     world.player_mut(runner).mods.add(Mod::Elsewhere, ModLifetime::Permanent);
                     world.player_mut(runner).swept_on = Some(game.day);
                 }
             },
             Event::ElsewhereReturn { ref returned, ref letters } => {
                 for &player in returned {
-                    println!("{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
-                    println!("Returned: {} after {} days", world.player(player).name, game.day - world.player(player).swept_on.unwrap());
-                    println!("Team: {}", world.team(world.player(player).team.unwrap()).name);
+                    apply_log!(info, "{} at {}, day {}", world.team(game.scoreboard.away_team.id).name, world.team(game.scoreboard.home_team.id).name, game.day);
+                    apply_log!(info, "Returned: {} after {} days", world.player(player).name, game.day - world.player(player).swept_on.unwrap());
+                    apply_log!(info, "Team: {}", world.team(world.player(player).team.unwrap()).name);
                     world.player_mut(player).mods.remove(Mod::Elsewhere);
                     world.player_mut(player).swept_on = None;
                 }
                 for i in 0..letters.len() {
                     let player = returned[i];
-                    println!("Scattered: {}, {} letters", world.player(player).name, letters[i]);
+                    apply_log!(debug, "Scattered: {}, {} letters", world.player(player).name, letters[i]);
                     if letters[i] > 0 {
                         world.player_mut(player).mods.add(Mod::Scattered, ModLifetime::Permanent);
                         world.player_mut(player).scattered_letters = letters[i];
@@ -819,7 +848,7 @@ impl Event {
                 for &player in unscattered {
                     world.player_mut(player).scattered_letters -= 1;
                     if world.player_mut(player).scattered_letters == 0 {
-                        println!("removed scattered from {}", world.player_mut(player).name);
+                        apply_log!(debug, "removed scattered from {}", world.player_mut(player).name);
                         world.player_mut(player).mods.remove(Mod::Scattered);
                     }
                 }
@@ -858,7 +887,139 @@ impl Event {
                 };
             }
         }
-        game.update_multiplier_data(world);
+        game.update_pitchers_of_record();
+        if self.is_pitch() {
+            game.pitches += 1;
+        }
+        //a plain pitch doesn't change any mods itself, but something outside the game might have
+        if !self.is_plain_pitch() || game.mods_generation != world.mods_generation() {
+            game.multiplier_data_dirty = true;
+            game.poll_cache.borrow_mut().clear();
+        }
+        if game.multiplier_data_dirty {
+            game.update_multiplier_data(world);
+        }
+        game.mods_generation = world.mods_generation();
+    }
+
+    //plain pitches don't touch mods, rosters, runners, batters or sides,
+    //so nothing derived from those needs recomputing after one
+    fn is_plain_pitch(&self) -> bool {
+        matches!(self, Event::Ball | Event::Strike | Event::Foul)
+    }
+
+    //events that don't touch the count, the runners, the score or who's up,
+    //so they can share a tick with each other, see Sim::next_batch
+    pub fn is_independent(&self) -> bool {
+        matches!(self, Event::Birds | Event::Party { .. } | Event::ElsewhereReturn { .. } | Event::Unscatter { .. })
+    }
+
+    //events where a pitch actually got thrown. charms, ambushes and intentional walks don't count
+    pub fn is_pitch(&self) -> bool {
+        matches!(
+            self,
+            Event::Ball
+                | Event::Strike
+                | Event::Foul
+                | Event::Strikeout
+                | Event::Walk
+                | Event::HomeRun
+                | Event::MagmaticHomeRun
+                | Event::BaseHit { .. }
+                | Event::GroundOut { .. }
+                | Event::Flyout { .. }
+                | Event::DoublePlay { .. }
+                | Event::FieldersChoice { .. }
+                | Event::HitByPitch { .. }
+                | Event::InstinctWalk { .. }
+                | Event::MildPitch
+                | Event::MildWalk
+        )
+    }
+
+    //what apply would do, worked out on copies of the game and world.
+    //that's a deep copy of every player and team each call, so it's for previews, not the sim loop.
+    //apply's logging is off while it runs, the preview didn't really happen
+    pub fn simulate(&self, game: &Game, world: &World) -> StateDelta {
+        let mut game_after = game.clone();
+        let mut world_after = world.clone();
+        PREVIEWING.with(|p| p.set(true));
+        self.apply(&mut game_after, &mut world_after);
+        PREVIEWING.with(|p| p.set(false));
+
+        let mut mods_added = Vec::new();
+        let mut mods_removed = Vec::new();
+        let before = world.players.iter().map(|(&id, p)| (id, &p.mods))
+            .chain(world.teams.iter().map(|(&id, t)| (id, &t.mods)));
+        for (id, mods) in before {
+            let mods_after = if let Some(player) = world_after.players.get(&id) {
+                &player.mods
+            } else {
+                &world_after.team(id).mods
+            };
+            mods_removed.extend(mods.iter().filter(|&(m, _)| !mods_after.has(m)).map(|(m, _)| (id, m)));
+            mods_added.extend(mods_after.iter().filter(|&(m, _)| !mods.has(m)).map(|(m, _)| (id, m)));
+        }
+        //players that didn't exist before, like incineration replacements
+        for (&id, player) in world_after.players.iter() {
+            if !world.players.contains_key(&id) {
+                mods_added.extend(player.mods.iter().map(|(m, _)| (id, m)));
+            }
+        }
+
+        StateDelta {
+            home_score: game_after.scoreboard.home_team.score - game.scoreboard.home_team.score,
+            away_score: game_after.scoreboard.away_team.score - game.scoreboard.away_team.score,
+            outs: game_after.outs - game.outs,
+            runners_before: game.runners,
+            runners_after: game_after.runners,
+            mods_added,
+            mods_removed,
+        }
+    }
+
+    //a rough "how much did this matter" for ranking weather events, 0 for everything else.
+    //events that hit one player scale with how good they were (the better of batting and pitching),
+    //so incinerating a star outranks incinerating a bench warmer
+    pub fn impact(&self, world: &World) -> f64 {
+        let value = |id: Uuid| {
+            let player = world.player(id);
+            player.batting().max(player.pitching())
+        };
+        match *self {
+            Event::Incineration { target, .. } => 10.0 * (1.0 + value(target)),
+            Event::Sun2 { .. } | Event::BlackHole { .. } => 10.0,
+            //a swap matters more the more lopsided it is
+            Event::Feedback { target1, target2 } => 3.0 + 5.0 * (value(target1) - value(target2)).abs(),
+            Event::Reverb { .. } => 4.0,
+            Event::Salmon { .. } => 3.0,
+            Event::BigPeanut { target } => 3.0 * (1.0 + value(target)),
+            Event::Blooddrain { target, .. } | Event::Soundproof { tangled: target, .. } => 2.0 * (1.0 + value(target)),
+            Event::Peanut { target, yummy } => if yummy { 0.5 } else { 1.0 + value(target) },
+            Event::Party { target, .. } => 1.0 + value(target),
+            Event::Swept { .. } | Event::NightShift { .. } => 2.0,
+            Event::Birds => 0.1,
+            _ => 0.0
+        }
+    }
+
+    //how long a live display should sit on this before the next one.
+    //presentation only, the sim never looks at it
+    pub fn display_duration(&self) -> Duration {
+        let secs = match self {
+            Event::Ball | Event::Strike | Event::Foul | Event::BatterUp { .. } => 2,
+            Event::HomeRun
+            | Event::MagmaticHomeRun
+            | Event::GameOver
+            | Event::Incineration { .. }
+            | Event::Feedback { .. }
+            | Event::Reverb { .. }
+            | Event::BlackHole { .. }
+            | Event::Sun2 { .. }
+            | Event::Salmon { .. } => 8,
+            _ => 4
+        };
+        Duration::from_secs(secs)
     }
 
     //todo: might merge this with a possible future print function
@@ -870,6 +1031,39 @@ impl Event {
     }
 }
 
+//the result of Event::simulate
+#[derive(Clone, Debug)]
+pub struct StateDelta {
+    pub home_score: f64,
+    pub away_score: f64,
+    pub outs: i16,
+    pub runners_before: Baserunners,
+    pub runners_after: Baserunners,
+    pub mods_added: Vec<(Uuid, Mod)>, //player or team id
+    pub mods_removed: Vec<(Uuid, Mod)>,
+}
+
+
+//the runs from the half-inning salmon is rewinding to, `resets` halves back from the latest.
+//element 0 is the running total Game::new puts in, so running out of halves
+//(the first inning, or salmon resetting over and over) just loses nothing
+fn salmon_runs(linescore: &[f64], resets: i16) -> f64 {
+    match (linescore.len() - 1).checked_sub(resets as usize) {
+        Some(idx) if idx > 0 => linescore[idx],
+        _ => 0.0
+    }
+}
+
+//runners_after gets worked out in BasePlugin against the runners at the time.
+//if it doesn't line up with what's on the bases now, applying it would teleport people
+fn check_runners_after(game: &Game, runners_after: &Baserunners) {
+    debug_assert!(
+        runners_after.follows_from(&game.runners),
+        "runners_after {:?} doesn't follow from current runners {:?}",
+        runners_after,
+        game.runners
+    );
+}
 
 fn upgrade_spicy(game: &mut Game, world: &mut World) {
     let batter = world.player_mut(game.batter().unwrap());
@@ -890,28 +1084,117 @@ fn downgrade_spicy(game: &mut Game, world: &mut World) {
      }
 }
 
+//the event kinds the sim ever looks back for in a game's log,
+//everything else only matters as the last event
+pub const QUERIED_EVENTS: &[&str] = &[
+    "InningSwitch",
+    "BaseHit",
+    "HomeRun",
+    "Salmon",
+    "Performing",
+    "TripleThreat",
+    "TripleThreatDeactivation",
+    "Undersea",
+    "UnderOver",
+    "OverUnder",
+    "MaintenanceMode",
+];
+
 #[derive(Clone, Debug)]
 pub struct Events {
-    events: Vec<String>
+    events: Vec<String>,
+    game_start: usize, //index of the first event of the current game
+    last: Option<String>, //kept even if it wasn't retained
+    total: usize,
+    kinds: Option<&'static [&'static str]>, //None keeps everything
+    cap: Option<usize>, //None keeps it all, see capped
 }
 
 impl Events {
     pub fn new() -> Events {
         Events {
-            events: Vec::new()
+            events: Vec::new(),
+            game_start: 0,
+            last: None,
+            total: 0,
+            kinds: None,
+            cap: None,
+        }
+    }
+    //a log that only holds on to the given kinds of event.
+    //has/count/etc. work as long as whatever they look for (and InningSwitch for windows) is in there
+    pub fn retaining(kinds: &'static [&'static str]) -> Events {
+        Events {
+            kinds: Some(kinds),
+            ..Events::new()
         }
     }
+    //a log that only holds on to the last `cap` events, for player feeds in long sims.
+    //queries only see what's still in there
+    pub fn capped(cap: usize) -> Events {
+        Events {
+            cap: Some(cap),
+            ..Events::new()
+        }
+    }
+    pub fn set_cap(&mut self, cap: Option<usize>) {
+        self.cap = cap;
+        self.evict();
+    }
+    //shifts the whole vec once it's full, fine for feed-sized caps
+    fn evict(&mut self) {
+        if let Some(cap) = self.cap {
+            if self.events.len() > cap {
+                let excess = self.events.len() - cap;
+                self.events.drain(..excess);
+                self.game_start = self.game_start.saturating_sub(excess);
+            }
+        }
+    }
+    //empties it out but keeps what it retains, its cap and its allocation
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.game_start = 0;
+        self.last = None;
+        self.total = 0;
+    }
     pub fn add(&mut self, repr: String) {
-        self.events.push(repr);
+        self.total += 1;
+        let retained = match self.kinds {
+            //reprs can have details tacked on, like "Undersea (true)"
+            Some(kinds) => kinds.iter().any(|&kind| repr == kind || repr.strip_prefix(kind).is_some_and(|rest| rest.starts_with(" ("))),
+            None => true,
+        };
+        if retained {
+            self.events.push(repr.clone());
+            self.evict();
+        }
+        self.last = Some(repr);
+    }
+    //player feeds run across games, this marks where the new one starts
+    pub fn start_game(&mut self) {
+        self.game_start = self.events.len();
+    }
+    //the last n retained events, oldest first
+    pub fn recent(&self, n: usize) -> &[String] {
+        &self.events[self.events.len().saturating_sub(n)..]
     }
+    pub fn since_game_start(&self) -> &[String] {
+        &self.events[self.game_start..]
+    }
+    //how many events it's holding on to right now
     pub fn len(&self) -> usize {
         self.events.len()
     }
+    //counts every event added, retained or not
+    pub fn total(&self) -> usize {
+        self.total
+    }
     pub fn last(&self) -> &String {
-        if self.events.len() == 0 {
+        if self.last.is_none() {
             panic!("don't call this when the game begins");
         }
-        self.events.last().unwrap()
+        self.last.as_ref().unwrap()
     }
     pub fn has(&self, s: String, limit: i16) -> bool {
         let mut half_innings = 0i16;
@@ -977,3 +1260,598 @@ impl Events {
         counter
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng::Rng, scenario::{test_world, GameScenario}, Weather};
+
+    //no wildcard, so a new variant won't compile until it's in here
+    fn variant_name(event: &Event) -> &'static str {
+        match event {
+            Event::BatterUp { .. } => "BatterUp",
+            Event::InningSwitch { .. } => "InningSwitch",
+            Event::GameOver => "GameOver",
+            Event::Ball => "Ball",
+            Event::Strike => "Strike",
+            Event::Foul => "Foul",
+            Event::Strikeout => "Strikeout",
+            Event::Walk => "Walk",
+            Event::IntentionalWalk => "IntentionalWalk",
+            Event::HomeRun => "HomeRun",
+            Event::BaseHit { .. } => "BaseHit",
+            Event::GroundOut { .. } => "GroundOut",
+            Event::Flyout { .. } => "Flyout",
+            Event::DoublePlay { .. } => "DoublePlay",
+            Event::FieldersChoice { .. } => "FieldersChoice",
+            Event::BaseSteal { .. } => "BaseSteal",
+            Event::CaughtStealing { .. } => "CaughtStealing",
+            Event::Party { .. } => "Party",
+            Event::Incineration { .. } => "Incineration",
+            Event::Peanut { .. } => "Peanut",
+            Event::Birds => "Birds",
+            Event::Feedback { .. } => "Feedback",
+            Event::Reverb { .. } => "Reverb",
+            Event::Blooddrain { .. } => "Blooddrain",
+            Event::Sun2 { .. } => "Sun2",
+            Event::BlackHole { .. } => "BlackHole",
+            Event::Salmon { .. } => "Salmon",
+            Event::PolaritySwitch => "PolaritySwitch",
+            Event::NightShift { .. } => "NightShift",
+            Event::Fireproof { .. } => "Fireproof",
+            Event::Soundproof { .. } => "Soundproof",
+            Event::BothSoundproof { .. } => "BothSoundproof",
+            Event::Reverberating { .. } => "Reverberating",
+            Event::Shelled { .. } => "Shelled",
+            Event::HitByPitch { .. } => "HitByPitch",
+            Event::PeckedFree { .. } => "PeckedFree",
+            Event::IffeyJr { .. } => "IffeyJr",
+            Event::Zap { .. } => "Zap",
+            Event::InstinctWalk { .. } => "InstinctWalk",
+            Event::BigPeanut { .. } => "BigPeanut",
+            Event::CharmWalk => "CharmWalk",
+            Event::CharmStrikeout => "CharmStrikeout",
+            Event::MildPitch => "MildPitch",
+            Event::MildWalk => "MildWalk",
+            Event::Repeating { .. } => "Repeating",
+            Event::FireEater { .. } => "FireEater",
+            Event::MagmaticHomeRun => "MagmaticHomeRun",
+            Event::CrowAmbush => "CrowAmbush",
+            Event::TasteTheInfinite { .. } => "TasteTheInfinite",
+            Event::Inhabiting { .. } => "Inhabiting",
+            Event::BlockedDrain { .. } => "BlockedDrain",
+            Event::Performing { .. } => "Performing",
+            Event::Beaned => "Beaned",
+            Event::PouredOver => "PouredOver",
+            Event::TripleThreat => "TripleThreat",
+            Event::TripleThreatDeactivation { .. } => "TripleThreatDeactivation",
+            Event::Swept { .. } => "Swept",
+            Event::Elsewhere { .. } => "Elsewhere",
+            Event::ElsewhereReturn { .. } => "ElsewhereReturn",
+            Event::Unscatter { .. } => "Unscatter",
+            Event::OverUnder { .. } => "OverUnder",
+            Event::UnderOver { .. } => "UnderOver",
+            Event::Undersea { .. } => "Undersea",
+            Event::MaintenanceMode { .. } => "MaintenanceMode",
+        }
+    }
+
+    //the sim looks events up by name (game.events.last() == "BaseHit" and so on),
+    //so the Display name has to start with the variant name even where strum adds to it
+    #[test]
+    fn repr_starts_with_variant_name() {
+        let mut rng = Rng::new(69, 420);
+        let id = Uuid::nil();
+        let runners = Baserunners::new(4);
+        let events = vec![
+            Event::BatterUp { batter: id },
+            Event::InningSwitch { inning: 1, top: true, runs_this_half: 0.0 },
+            Event::GameOver,
+            Event::Ball,
+            Event::Strike,
+            Event::Foul,
+            Event::Strikeout,
+            Event::Walk,
+            Event::IntentionalWalk,
+            Event::HomeRun,
+            Event::BaseHit { bases: 1, runners_after: runners },
+            Event::GroundOut { fielder: id, runners_after: runners },
+            Event::Flyout { fielder: id, runners_after: runners },
+            Event::DoublePlay { runners_after: runners },
+            Event::FieldersChoice { runners_after: runners },
+            Event::BaseSteal { runner: id, base_from: 0, base_to: 1 },
+            Event::CaughtStealing { runner: id, base_from: 0 },
+            Event::Party { target: id, boosts: vec![] },
+            Event::Incineration { target: id, replacement: Player::new(&mut rng), chain: None, ambush: (None, None) },
+            Event::Peanut { target: id, yummy: false },
+            Event::Birds,
+            Event::Feedback { target1: id, target2: id },
+            Event::Reverb { reverb_type: ReverbType::Full, team: id, changes: vec![] },
+            Event::Blooddrain { drainer: id, target: id, stat: StatCategory::Batting, siphon: false, siphon_effect: -1 },
+            Event::Sun2 { home_team: true },
+            Event::BlackHole { home_team: true, carcinized: None },
+            Event::Salmon { home_runs_lost: false, away_runs_lost: false },
+            Event::PolaritySwitch,
+            Event::NightShift { batter: true, replacement: id, replacement_idx: 0, boosts: vec![] },
+            Event::Fireproof { target: id },
+            Event::Soundproof { resists: id, tangled: id, decreases: vec![] },
+            Event::BothSoundproof { target1: id, target2: id },
+            Event::Reverberating { batter: id },
+            Event::Shelled { batter: id },
+            Event::HitByPitch { target: id, hbp_type: 0 },
+            Event::PeckedFree { player: id },
+            Event::IffeyJr { target: id },
+            Event::Zap { batter: true },
+            Event::InstinctWalk { third: false },
+            Event::BigPeanut { target: id },
+            Event::CharmWalk,
+            Event::CharmStrikeout,
+            Event::MildPitch,
+            Event::MildWalk,
+            Event::Repeating { batter: id },
+            Event::FireEater { target: id },
+            Event::MagmaticHomeRun,
+            Event::CrowAmbush,
+            Event::TasteTheInfinite { target: id },
+            Event::Inhabiting { batter: id, inhabit: id },
+            Event::BlockedDrain { drainer: id, target: id },
+            Event::Performing { overperforming: vec![], underperforming: vec![] },
+            Event::Beaned,
+            Event::PouredOver,
+            Event::TripleThreat,
+            Event::TripleThreatDeactivation { home: true, away: false },
+            Event::Swept { elsewhere: vec![] },
+            Event::Elsewhere { batter: id },
+            Event::ElsewhereReturn { returned: vec![], letters: vec![] },
+            Event::Unscatter { unscattered: vec![] },
+            Event::OverUnder { on: true, players: vec![] },
+            Event::UnderOver { on: true, players: vec![] },
+            Event::Undersea { home: true },
+            Event::MaintenanceMode { home: true },
+        ];
+        for event in events.iter() {
+            let repr = event.repr();
+            assert!(repr.starts_with(variant_name(event)), "{} doesn't start with {}", repr, variant_name(event));
+        }
+        assert_eq!(Event::BatterUp { batter: id }.repr(), "BatterUp");
+    }
+
+    //home and away wins after the event, starting from 0-0 with whichever side at 10
+    fn overflow(weather: Weather, home_team: bool, day: usize) -> (i16, i16) {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(weather).day(day).build(&mut world, &mut rng);
+        if home_team {
+            game.scoreboard.home_team.score = 10.0;
+        } else {
+            game.scoreboard.away_team.score = 10.0;
+        }
+        let event = match weather {
+            Weather::Sun2 => Event::Sun2 { home_team },
+            _ => Event::BlackHole { home_team, carcinized: None },
+        };
+        event.apply(&mut game, &mut world);
+        assert_eq!(game.scoreboard.home_team.score, 0.0);
+        assert_eq!(game.scoreboard.away_team.score, 0.0);
+        if day > 98 {
+            (world.team(home).postseason_wins, world.team(away).postseason_wins)
+        } else {
+            (world.team(home).wins, world.team(away).wins)
+        }
+    }
+
+    //sun 2 gives the overflowing team a win
+    #[test]
+    fn sun2_credits_the_overflowing_team() {
+        assert_eq!(overflow(Weather::Sun2, true, 0), (1, 0));
+        assert_eq!(overflow(Weather::Sun2, false, 0), (0, 1));
+        assert_eq!(overflow(Weather::Sun2, true, 99), (1, 0));
+        assert_eq!(overflow(Weather::Sun2, false, 99), (0, 1));
+    }
+
+    //the black hole swallows a win from the overflowing team's opponent
+    #[test]
+    fn black_hole_takes_from_the_opponent() {
+        assert_eq!(overflow(Weather::BlackHole, true, 0), (0, -1));
+        assert_eq!(overflow(Weather::BlackHole, false, 0), (-1, 0));
+        assert_eq!(overflow(Weather::BlackHole, true, 99), (0, -1));
+        assert_eq!(overflow(Weather::BlackHole, false, 99), (-1, 0));
+    }
+
+    //mods changed from outside (World::give_mod, a team's mods directly) still get picked up
+    //on the next event, even a plain pitch
+    #[test]
+    fn outside_mod_change_recomputes() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        Event::Ball.apply(&mut game, &mut world);
+        assert!(!game.multiplier_data.batting_team_mods.has(Mod::Fireproof));
+
+        world.give_team_mod(away, Mod::Fireproof, ModLifetime::Game);
+        Event::Ball.apply(&mut game, &mut world);
+        assert!(game.multiplier_data.batting_team_mods.has(Mod::Fireproof));
+        assert!(!game.multiplier_data_dirty);
+    }
+
+    //a team mod slipped in behind the world's back shows which events recompute:
+    //a plain ball leaves the cached mods alone, a feedback swap doesn't
+    #[test]
+    fn only_real_changes_recompute() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Feedback).build(&mut world, &mut rng);
+        let batter = world.team(away).lineup[0];
+        game.scoreboard.away_team.batter = Some(batter);
+        Event::Ball.apply(&mut game, &mut world);
+
+        world.team_mut(away).mods.add(Mod::Fireproof, ModLifetime::Game);
+        let generation = world.mods_generation();
+        Event::Ball.apply(&mut game, &mut world);
+        assert!(!game.multiplier_data.batting_team_mods.has(Mod::Fireproof));
+
+        //re-giving a mod that's already there isn't a change either
+        world.give_team_mod(home, Mod::Fireproof, ModLifetime::Permanent);
+        world.give_team_mod(home, Mod::Fireproof, ModLifetime::Permanent);
+        assert_eq!(world.mods_generation(), generation + 1);
+
+        let fielder = world.team(home).lineup[0];
+        Event::Feedback { target1: batter, target2: fielder }.apply(&mut game, &mut world);
+        assert!(game.multiplier_data.batting_team_mods.has(Mod::Fireproof));
+    }
+
+    #[test]
+    fn incineration_draws_from_the_pool() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Eclipse).build(&mut world, &mut rng);
+        let mut prospect = Player::new_with_id(world.new_id(&mut rng), &mut rng);
+        prospect.name = String::from("Prospect");
+        let prospect_id = prospect.id;
+        world.replacement_pool.push(prospect.clone());
+
+        let target = world.team(away).lineup[3];
+        Event::Incineration { target, replacement: prospect, chain: None, ambush: (None, None) }.apply(&mut game, &mut world);
+        assert!(world.replacement_pool.is_empty());
+        assert_eq!(world.team(away).lineup[3], prospect_id);
+        assert_eq!(world.player(prospect_id).name, "Prospect");
+        assert_eq!(world.player(prospect_id).team, Some(away));
+    }
+
+    #[test]
+    fn capped_keeps_exactly_the_cap() {
+        let mut events = Events::capped(3);
+        for i in 0..5 {
+            events.add(i.to_string());
+        }
+        assert_eq!(events.recent(10), ["2", "3", "4"]);
+        assert_eq!(events.len(), 3);
+        assert_eq!(events.total(), 5);
+        events.set_cap(Some(1));
+        assert_eq!(events.recent(10), ["4"]);
+    }
+
+    #[test]
+    fn recent_and_since_game_start() {
+        let mut events = Events::new();
+        assert!(events.recent(3).is_empty());
+        for repr in ["Strike", "Ball", "Foul"] {
+            events.add(String::from(repr));
+        }
+        events.start_game();
+        assert!(events.since_game_start().is_empty());
+        for repr in ["HomeRun", "Walk"] {
+            events.add(String::from(repr));
+        }
+        assert_eq!(events.recent(3), ["Foul", "HomeRun", "Walk"]);
+        assert_eq!(events.recent(1), ["Walk"]);
+        assert_eq!(events.recent(0), [] as [&str; 0]);
+        assert_eq!(events.since_game_start(), ["HomeRun", "Walk"]);
+    }
+
+    //pecked free from the shell and then peanutted: superallergic takes a bigger hit than a plain allergic player
+    #[test]
+    fn pecked_free_peanut_is_superallergic() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Peanuts).build(&mut world, &mut rng);
+        let pecked = world.team(away).lineup[0];
+        let allergic = world.team(away).lineup[1];
+        world.player_mut(pecked).mods.add(Mod::Shelled, ModLifetime::Permanent);
+        let pecked_before = world.player(pecked).thwackability;
+        let allergic_before = world.player(allergic).thwackability;
+
+        Event::PeckedFree { player: pecked }.apply(&mut game, &mut world);
+        assert!(!world.player(pecked).mods.has(Mod::Shelled));
+        assert!(world.player(pecked).mods.has(Mod::Superallergic));
+
+        Event::Peanut { target: pecked, yummy: false }.apply(&mut game, &mut world);
+        Event::Peanut { target: allergic, yummy: false }.apply(&mut game, &mut world);
+        let pecked_drop = pecked_before - world.player(pecked).thwackability;
+        let allergic_drop = allergic_before - world.player(allergic).thwackability;
+        assert!((pecked_drop + mods::SUPERALLERGIC_PENALTY).abs() < 1e-9);
+        assert!((allergic_drop - 0.2).abs() < 1e-9);
+        assert!(pecked_drop > allergic_drop);
+    }
+
+    //a two run homer, previewed: the delta has the runs, the real game doesn't
+    #[test]
+    fn simulate_home_run() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let runner = world.team(away).lineup[1];
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).runner(1, runner).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+
+        let delta = Event::HomeRun.simulate(&game, &world);
+        assert_eq!(delta.away_score, 2.0);
+        assert_eq!(delta.home_score, 0.0);
+        assert_eq!(delta.outs, 0);
+        assert!(delta.runners_after.empty());
+        assert!(delta.mods_added.is_empty() && delta.mods_removed.is_empty());
+
+        assert_eq!(game.scoreboard.away_team.score, 0.0);
+        assert_eq!(game.runners.at(1), Some(runner));
+        assert_eq!(game.events.total(), 0);
+        assert!(!PREVIEWING.with(|p| p.get()));
+    }
+
+    //hit with the bases loaded: one run in, everyone else up a base, batter on first
+    #[test]
+    fn hit_by_pitch_bases_loaded() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .runner(2, lineup[1])
+            .runner(1, lineup[2])
+            .runner(0, lineup[3])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+
+        Event::HitByPitch { target: lineup[0], hbp_type: 0 }.apply(&mut game, &mut world);
+        assert_eq!(game.scoreboard.away_team.score, 1.0);
+        assert_eq!(game.runners.at(2), Some(lineup[2]));
+        assert_eq!(game.runners.at(1), Some(lineup[3]));
+        assert_eq!(game.runners.at(0), Some(lineup[0]));
+        assert_eq!(game.runners.len(), 3);
+        assert!(world.player(lineup[0]).mods.has(Mod::Unstable));
+    }
+
+    //salmon after the top of the first: home hasn't had a half yet, so there's nothing of theirs to take
+    #[test]
+    fn salmon_in_the_first_inning() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Salmon).build(&mut world, &mut rng);
+        game.scoreboard.away_team.score = 1.0;
+        Event::InningSwitch { inning: 1, top: false, runs_this_half: 1.0 }.apply(&mut game, &mut world);
+
+        Event::Salmon { home_runs_lost: true, away_runs_lost: true }.apply(&mut game, &mut world);
+        assert_eq!(game.scoreboard.away_team.score, 0.0);
+        assert_eq!(game.scoreboard.home_team.score, 0.0);
+        assert_eq!(game.inning, 1);
+        assert!(game.scoreboard.top);
+
+        //and resetting further back than the game goes just loses nothing
+        assert_eq!(salmon_runs(&game.linescore_away, 5), 0.0);
+    }
+
+    //incinerating someone on base puts the replacement there in their place
+    #[test]
+    fn incinerated_runner_is_replaced_on_base() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let runner = world.team(away).lineup[1];
+        let mut game = GameScenario::new(home, away).weather(Weather::Eclipse).runner(1, runner).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        let replacement = Player::new_with_id(world.new_id(&mut rng), &mut rng);
+        let replacement_id = replacement.id;
+
+        Event::Incineration { target: runner, replacement, chain: None, ambush: (None, None) }.apply(&mut game, &mut world);
+        assert_eq!(game.runners.at(1), Some(replacement_id));
+        assert!(!game.runners.contains(runner));
+        assert_eq!(world.team(away).lineup[1], replacement_id);
+    }
+
+    //neither soundproof target gets tangled, so nobody's stats go down
+    #[test]
+    fn both_soundproof_leaves_stats_alone() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let target1 = world.team(away).lineup[0];
+        let target2 = world.team(home).lineup[0];
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Feedback)
+            .player_mod(target1, Mod::Soundproof, ModLifetime::Permanent)
+            .player_mod(target2, Mod::Soundproof, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(target1);
+        let stats = |world: &World, id: Uuid| {
+            let player = world.player(id);
+            (player.batting(), player.pitching(), player.defense(), player.baserunning(), player.team)
+        };
+        let before = (stats(&world, target1), stats(&world, target2));
+
+        Event::BothSoundproof { target1, target2 }.apply(&mut game, &mut world);
+        assert_eq!((stats(&world, target1), stats(&world, target2)), before);
+        assert!(!game.home_impaired && !game.away_impaired);
+    }
+
+    //collects what's logged on the current thread, so tests running alongside don't mix in
+    struct TestLogger;
+
+    thread_local! {
+        static LOGGED: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    impl log::Log for TestLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            LOGGED.with(|logged| logged.borrow_mut().push(record.args().to_string()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn logged_by(f: impl FnOnce()) -> Vec<String> {
+        static LOGGER: TestLogger = TestLogger;
+        static INIT: std::sync::Once = std::sync::Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(log::LevelFilter::Debug);
+        });
+        LOGGED.with(|logged| logged.borrow_mut().clear());
+        f();
+        LOGGED.with(|logged| logged.take())
+    }
+
+    //incinerations go through the log crate, where whoever's using the library can pick them up.
+    //a preview of one doesn't
+    #[test]
+    fn incineration_is_logged() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Eclipse).build(&mut world, &mut rng);
+        let target = world.team(away).lineup[2];
+        let name = world.player(target).name.clone();
+        let event = Event::Incineration { target, replacement: Player::new_with_id(world.new_id(&mut rng), &mut rng), chain: None, ambush: (None, None) };
+
+        assert!(logged_by(|| { event.simulate(&game, &world); }).is_empty());
+        let logged = logged_by(|| event.apply(&mut game, &mut world));
+        assert!(logged.contains(&format!("Incineration: {}", name)), "{:?}", logged);
+    }
+
+    //each reverb type on the batting team: who moves, and who ends up up to bat and pitching
+    #[test]
+    fn reverb_by_type() {
+        for reverb_type in [ReverbType::Full, ReverbType::Several, ReverbType::Lineup, ReverbType::Rotation] {
+            let (mut world, mut rng, home, away) = test_world(12);
+            let mut game = GameScenario::new(home, away).weather(Weather::Reverb).day(3).build(&mut world, &mut rng);
+            game.scoreboard.away_team.batter_index = 2;
+            game.scoreboard.away_team.batter = Some(world.team(away).lineup[2]);
+            let before = world.team(away).clone();
+            let batter_before = game.batter();
+            let pitcher_before = game.scoreboard.away_team.pitcher;
+            let home_pitcher = game.pitcher();
+
+            let changes = world.team(away).roll_reverb_changes(&mut rng, reverb_type, &[]);
+            Event::Reverb { reverb_type, team: away, changes }.apply(&mut game, &mut world);
+            let after = world.team(away);
+
+            let mut roster_before: Vec<Uuid> = before.lineup.iter().chain(before.rotation.iter()).copied().collect();
+            let mut roster_after: Vec<Uuid> = after.lineup.iter().chain(after.rotation.iter()).copied().collect();
+            roster_before.sort();
+            roster_after.sort();
+            assert_eq!(roster_after, roster_before, "{:?}", reverb_type);
+            assert_eq!((after.lineup.len(), after.rotation.len()), (before.lineup.len(), before.rotation.len()));
+
+            if reverb_type.affects_lineup() {
+                assert_eq!(game.batter(), Some(after.lineup[2]), "{:?}", reverb_type);
+            } else {
+                assert_eq!(after.lineup, before.lineup);
+                assert_eq!(game.batter(), batter_before);
+            }
+            if reverb_type.affects_rotation() {
+                assert_eq!(game.scoreboard.away_team.pitcher, after.rotation[3 % after.rotation.len()], "{:?}", reverb_type);
+            } else {
+                assert_eq!(after.rotation, before.rotation);
+                assert_eq!(game.scoreboard.away_team.pitcher, pitcher_before);
+            }
+            //the other team's pitcher never moves
+            assert_eq!(game.pitcher(), home_pitcher);
+        }
+    }
+
+    //(runs, who's on first/second/third after) for an instinct walk with the given runners (base, lineup slot)
+    fn instinct_walk(third: bool, runners: &[(u8, usize)]) -> (f64, [Option<Uuid>; 3], Vec<Uuid>) {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut scenario = GameScenario::new(home, away).weather(Weather::Sun);
+        for &(base, slot) in runners {
+            scenario = scenario.runner(base, lineup[slot]);
+        }
+        let mut game = scenario.build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        Event::InstinctWalk { third }.apply(&mut game, &mut world);
+        (game.scoreboard.away_team.score, [game.runners.at(0), game.runners.at(1), game.runners.at(2)], lineup)
+    }
+
+    #[test]
+    fn instinct_walk_forces_runners_ahead() {
+        //to third with someone already there: they're forced in
+        let (runs, bases, lineup) = instinct_walk(true, &[(2, 1)]);
+        assert_eq!(runs, 1.0);
+        assert_eq!(bases, [None, None, Some(lineup[0])]);
+
+        //to third from first: the runner has to stay ahead of the batter, so they score too
+        let (runs, bases, lineup) = instinct_walk(true, &[(0, 1)]);
+        assert_eq!(runs, 1.0);
+        assert_eq!(bases, [None, None, Some(lineup[0])]);
+
+        //to second with first and second taken: second scores, first goes to third
+        let (runs, bases, lineup) = instinct_walk(false, &[(1, 1), (0, 2)]);
+        assert_eq!(runs, 1.0);
+        assert_eq!(bases, [None, Some(lineup[0]), Some(lineup[2])]);
+
+        //to second with only third taken: nobody's forced
+        let (runs, bases, lineup) = instinct_walk(false, &[(2, 1)]);
+        assert_eq!(runs, 0.0);
+        assert_eq!(bases, [None, Some(lineup[0]), Some(lineup[1])]);
+    }
+
+
+    //a strikeout against a triple threat pitcher costs runs, unless the ruleset turns it off
+    #[test]
+    fn triple_threat_penalty_toggle() {
+        for penalty in [true, false] {
+            let (mut world, mut rng, home, away) = test_world(12);
+            world.ruleset.triple_threat_penalty = penalty;
+            let mut game = GameScenario::new(home, away).weather(Weather::Coffee3).build(&mut world, &mut rng);
+            Event::TripleThreat.apply(&mut game, &mut world);
+            game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+            //it only bites with three balls or someone on third
+            game.balls = 3;
+            assert!(game.triple_threat_active(&world));
+            Event::Strikeout.apply(&mut game, &mut world);
+            let expected = if penalty { TRIPLE_THREAT_PENALTY } else { 0.0 };
+            assert_eq!(game.scoreboard.away_team.score, expected);
+        }
+    }
+
+
+    //runner on second, but the ground out says they end up on first
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "doesn't follow from current runners")]
+    fn ground_out_with_runners_going_backwards() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .runner(1, lineup[1])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        let mut runners_after = Baserunners::new(4);
+        runners_after.add(0, lineup[1]);
+        let fielder = world.team(home).lineup[0];
+        Event::GroundOut { fielder, runners_after }.apply(&mut game, &mut world);
+    }
+
+
+    #[test]
+    fn home_runs_linger_longer_than_balls() {
+        assert!(Event::HomeRun.display_duration() > Event::Ball.display_duration());
+        assert!(Event::Walk.display_duration() > Event::Ball.display_duration());
+        assert!(Event::HomeRun.display_duration() > Event::Walk.display_duration());
+    }
+
+
+    #[test]
+    fn incinerating_a_star_has_more_impact() {
+        let (mut world, mut rng, _, away) = test_world(12);
+        let star = world.team(away).lineup[0];
+        let scrub = world.team(away).lineup[1];
+        world.player_mut(star).boost_category(StatCategory::Batting, 1.0);
+        world.player_mut(star).boost_category(StatCategory::Pitching, 1.0);
+        let incinerate = |target: Uuid, rng: &mut Rng| Event::Incineration { target, replacement: Player::new(rng), chain: None, ambush: (None, None) };
+        let star_impact = incinerate(star, &mut rng).impact(&world);
+        let scrub_impact = incinerate(scrub, &mut rng).impact(&world);
+        assert!(star_impact > scrub_impact, "{} vs {}", star_impact, scrub_impact);
+        //and either way it outranks some birds
+        assert!(scrub_impact > Event::Birds.impact(&world));
+    }
+}
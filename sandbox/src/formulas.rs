@@ -6,7 +6,14 @@ use crate::{MultiplierData, Weather};
 //however the sim in its current state operates with DE assumptions.
 //This is fixed with season rulesets
 
-pub fn strike_threshold(pitcher: &Player, batter: &Player, flinch: bool, season_ruleset: u8, multiplier_data: &MultiplierData) -> f64 {
+//Flinch batters don't swing on strike 0 (or almost never, see swing_threshold)
+//and get thrown more strikes for it
+pub fn is_flinching(batter: &Player, strikes: i16) -> bool {
+    strikes == 0 && batter.mods.has(Mod::Flinch)
+}
+
+pub fn strike_threshold(pitcher: &Player, batter: &Player, strikes: i16, season_ruleset: u8, multiplier_data: &MultiplierData) -> f64 {
+    let flinch = is_flinching(batter, strikes);
     let fwd = 0.5; // todo: ballparks
     let ruth = coeff(PlayerAttr::Ruthlessness, &pitcher.legendary_item, &pitcher.mods, multiplier_data, false, pitcher.ruthlessness) * (1.0 + 0.2 * pitcher.vibes(multiplier_data.day));
     let vibeless_musc = coeff(PlayerAttr::Musclitude, &batter.legendary_item, &batter.mods, multiplier_data, true, batter.musclitude);
@@ -21,7 +28,12 @@ pub fn strike_threshold(pitcher: &Player, batter: &Player, flinch: bool, season_
     }
 }
 
-pub fn swing_threshold(pitcher: &Player, batter: &Player, is_strike: bool, season_ruleset: u8, multiplier_data: &MultiplierData) -> f64 {
+pub fn swing_threshold(pitcher: &Player, batter: &Player, is_strike: bool, strikes: i16, season_ruleset: u8, multiplier_data: &MultiplierData) -> f64 {
+    //not quite never, so something stacked on top of Flinch still has a chance to matter.
+    //anything that should let them swing anyway goes here
+    if is_flinching(batter, strikes) {
+        return 0.001;
+    }
     let visc = 0.5;
     if is_strike {
         let combined_batting = (coeff(PlayerAttr::Divinity, &batter.legendary_item, &batter.mods, multiplier_data, true, batter.divinity) * (1.0 + 0.2 * batter.vibes(multiplier_data.day))
@@ -227,6 +239,28 @@ pub fn steal_success_threshold(_runner: &Player, _defender: &Player) -> f64 {
     0.8
 }
 
+//only used with World::foul_tips on, so it's made up
+pub fn foul_tip_threshold(_season_ruleset: u8) -> f64 {
+    0.05
+}
+
+//same chance for zapping a strike or a ball, no known ruleset differences
+pub fn electric_threshold(_season_ruleset: u8) -> f64 {
+    0.2
+}
+
+//how much a party boosts each stat by. life of the party is 20% more.
+//same in every ruleset as far as we know
+pub fn party_boost(life_of_party: bool, _season_ruleset: u8) -> f64 {
+    if life_of_party { 0.048 } else { 0.04 }
+}
+
+//chance a reverberating player bats again right after their pa.
+//a rough estimate, nobody's pinned it down
+pub fn reverberating_threshold() -> f64 {
+    0.2
+}
+
 //all out formulas are consistent across all seasons. probably
 
 pub fn hit_advancement_threshold(runner: &Player, fielder: &Player, _season_ruleset: u8, multiplier_data: &MultiplierData) -> f64 {
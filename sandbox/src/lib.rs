@@ -1,19 +1,28 @@
+use std::{cell::RefCell, cmp::Ordering, collections::BTreeMap};
+
 use bases::Baserunners;
-use entities::World;
-use mods::{Mod, Mods};
+use entities::{Player, World};
+use mods::{Mod, Mods, BLASERUNNING_BONUS, FLIPPERS_BONUS, TIRED_PENALTY, TRIPLE_THREAT_PENALTY, WIRED_BONUS};
 use rng::Rng;
 use uuid::Uuid;
-use events::Events;
+use events::{Event, Events};
 
 pub mod bases;
 pub mod entities;
 pub mod formulas;
 pub mod mods;
 pub mod rng;
+pub mod ruleset;
+pub mod scenario;
+pub mod schedule;
 pub mod sim;
 pub mod events;
 
-#[derive(Clone, Copy, Debug)]
+//scores closer than this count as tied. runs aren't always whole
+//(sun point one, polarity, ...) so float noise creeps in
+pub const SCORE_EPSILON: f64 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq, strum::Display, strum::EnumString)]
 pub enum Weather {
     Sun,
     Eclipse,
@@ -104,11 +113,25 @@ pub struct Game {
     pub balls: i16,
     pub strikes: i16,
     pub outs: i16,
+    pub fouls: u32, //this pa
+    //for fuzzing: once a pa has this many fouls the next one is a strikeout,
+    //so a bad formula can't stretch an at-bat forever. None (no limit) normally
+    pub max_fouls: Option<u32>,
+    pitches: u32, //the whole game, both teams. see pitch_count
 
     pub polarity: bool, //false for positive, true for negative
+    //events that scored at least one run this half-inning, however many runs each.
+    //base_sweep counts them as it clears runners off home, except a solo home run
+    //which has nobody to sweep and counts itself. sum sun reads it in get_run_value
     pub scoring_plays_inning: u8,
     pub salmon_resets_inning: i16,
+    pub reverberations_inning: u8, //capped at mods::REVERBERATING_LIMIT
     pub multiplier_data: MultiplierData,
+    pub multiplier_data_dirty: bool, //apply only recomputes multiplier_data when this is set
+    mods_generation: u64, //World::mods_generation as of the last recompute, anything newer sets the flag above
+    //sim::poll_for_mod results by (mod, exclusion, team), cleared by anything but a plain pitch.
+    //a RefCell for the same reason as rng_trace
+    pub poll_cache: RefCell<BTreeMap<PollKey, Vec<Uuid>>>,
 
     pub events: Events,
     pub started: bool,
@@ -120,8 +143,36 @@ pub struct Game {
     pub home_impaired: bool,
     pub away_impaired: bool,
 
-    pub linescore_home: Vec<f64>, //for salmon purposes
+    pub crowd: f64, //1.0 is a normal crowd, scales home field advantage. see set_crowd
+
+    pub linescore_home: Vec<f64>, //runs per half-inning, salmon uses these too
     pub linescore_away: Vec<f64>, //the first element is the total score
+
+    //who was leading and each side's pitcher at the last lead change,
+    //None while it's tied. by the end this is who gets the W and the L
+    pub pitchers_of_record: Option<(Ordering, Uuid, Uuid)>, //lead, home pitcher, away pitcher
+
+    pub rbis: BTreeMap<Uuid, u8>, //box score, by batter. nothing in the sim reads it
+    pub strikeouts: BTreeMap<Uuid, u8>, //box score, by pitcher. crow ambushes count as strikeouts
+    pub runs_allowed: BTreeMap<Uuid, u8>, //box score, by pitcher. counted in runners like rbis
+    pub putouts: BTreeMap<Uuid, u8>, //box score, by fielder. only flyouts and groundouts say who fielded them
+
+    //for chasing desyncs against real games: Some records every labeled roll in do_pitch.
+    //a RefCell since plugins only get to look at the game
+    pub rng_trace: Option<RefCell<Vec<(String, f64)>>>,
+}
+
+pub type PollKey = (Mod, &'static str, bool); //mod, exclusion, team
+
+//how a finished game turned out, see Game::result
+#[derive(Clone, Debug)]
+pub struct GameResult {
+    pub winning_team: Uuid,
+    pub losing_team: Uuid,
+    pub winning_score: f64,
+    pub losing_score: f64,
+    pub winning_pitcher: Uuid,
+    pub losing_pitcher: Uuid,
 }
 
 #[derive(Clone, Debug)]
@@ -136,6 +187,10 @@ pub struct GameTeam {
     pub id: Uuid,
     pub pitcher: Uuid,
     pub batter: Option<Uuid>,
+    pub inhabited: Option<Uuid>, //the haunted batter a ghost is batting for, until the PA ends
+    //while someone's up, lineup[batter_index % len] is their slot (a ghost bats in the slot
+    //of who they're inhabiting). only moves through Game::shift_batter_index:
+    //end_pa and skipped batters +1, reverberating and repeating -1 back to whoever just batted
     pub batter_index: usize,
     pub score: f64, // sigh
     pub max_outs: i16,
@@ -161,19 +216,26 @@ pub struct MultiplierData {
 // like have `tick` not actually make any changes to the game state but instead apply that based on the EventData
 impl Game {
     pub fn new(team_a: Uuid, team_b: Uuid, day: usize, weather_override: Option<Weather>, world: &World, rng: &mut Rng) -> Game {
-        let weather = if weather_override.is_some() { weather_override.unwrap() } else { Weather::generate(rng, world.season_ruleset, day) };
+        let weather = if weather_override.is_some() { weather_override.unwrap() } else { Weather::generate(rng, world.ruleset.season, day) };
         Game {
-            id: Uuid::new_v4(),
+            id: world.new_id(rng),
             weather,
             day,
             inning: 1,
             balls: 0,
             strikes: 0,
             outs: 0,
+            fouls: 0,
+            max_fouls: None,
+            pitches: 0,
             polarity: false,
             scoring_plays_inning: 0,
             salmon_resets_inning: 0,
-            events: Events::new(),
+            reverberations_inning: 0,
+            multiplier_data_dirty: true, //anything set up before the first event gets picked up
+            mods_generation: world.mods_generation(),
+            poll_cache: RefCell::new(BTreeMap::new()),
+            events: Events::retaining(events::QUERIED_EVENTS),
             multiplier_data: MultiplierData {
                 //someone who knows about lifetimes more than me can probably
                 //make this code more efficient
@@ -191,30 +253,68 @@ impl Game {
                 home_team: GameTeam {
                     id: team_a,
                     //todo: days
-                    pitcher: world.team(team_a).rotation[day % world.team(team_a).rotation.len()],
+                    pitcher: Game::starting_pitcher(team_a, day, world),
                     batter: None,
+                    inhabited: None,
                     batter_index: 0,
                     score: if world.team(team_a).mods.has(Mod::HomeFieldAdvantage) { 1.0 } else { 0.0 },
                     max_outs: 3
                 },
                 away_team: GameTeam {
                     id: team_b,
-                    pitcher: world.team(team_b).rotation[day % world.team(team_b).rotation.len()],
+                    pitcher: Game::starting_pitcher(team_b, day, world),
                     batter: None,
+                    inhabited: None,
                     batter_index: 0,
                     score: 0.0,
                     max_outs: 3
                 },
                 top: true,
             },
-            runners: Baserunners::new(if world.team(team_b).mods.has(Mod::FifthBase) { 5 } else { 4 }),
+            runners: Baserunners::new(Game::team_bases(team_b, world)), //away bats first
             home_impaired: false,
             away_impaired: false,
+            crowd: 1.0,
+            //always starts with the total, whatever the weather. salmon relies on it
             linescore_home: vec![if world.team(team_a).mods.has(Mod::HomeFieldAdvantage) { 1.0 } else { 0.0 }],
             linescore_away: vec![0.0],
+            pitchers_of_record: None,
+            rbis: BTreeMap::new(),
+            strikeouts: BTreeMap::new(),
+            runs_allowed: BTreeMap::new(),
+            putouts: BTreeMap::new(),
+            rng_trace: None,
         }
     }
 
+    //turns this into Game::new with the same arguments, but holds on to the event log's allocation.
+    //for playing lots of games back to back
+    pub fn reset(&mut self, team_a: Uuid, team_b: Uuid, day: usize, weather_override: Option<Weather>, world: &World, rng: &mut Rng) {
+        let mut events = std::mem::replace(&mut self.events, Events::new());
+        events.clear();
+        *self = Game::new(team_a, team_b, day, weather_override, world, rng);
+        self.events = events;
+    }
+
+    //the free run HomeFieldAdvantage puts on the board, bigger with a bigger crowd
+    pub fn home_field_bonus(&self, world: &World) -> f64 {
+        if world.team(self.scoreboard.home_team.id).mods.has(Mod::HomeFieldAdvantage) {
+            self.crowd
+        } else {
+            0.0
+        }
+    }
+
+    //the home field run is already on the board by the time the game exists,
+    //so this swaps it out for the rescaled one
+    pub fn set_crowd(&mut self, crowd: f64, world: &World) {
+        let old_bonus = self.home_field_bonus(world);
+        self.crowd = crowd;
+        let diff = self.home_field_bonus(world) - old_bonus;
+        self.scoreboard.home_team.score += diff;
+        self.linescore_home[0] += diff;
+    }
+
     fn base_sweep(&mut self) {
         let mut new_runners = Baserunners::new(self.runners.base_number);
         let mut scoring_play = false;
@@ -233,12 +333,15 @@ impl Game {
     }
 
     //note that this is only for runs scored on a regular event
-    fn score(&mut self, world: &mut World) {
+    //returns how many runners came home, for rbis
+    fn score(&mut self, world: &mut World) -> u8 {
+        let mut runners_scored = 0;
         if self.outs < self.scoreboard.batting_team().max_outs {
             let mut runs_scored = 0.0;
             for runner in self.runners.iter() {
                 if runner.base >= self.runners.base_number - 1 {
-                    runs_scored += self.get_run_value();
+                    runners_scored += 1;
+                    runs_scored += self.get_run_value(world);
                     runs_scored += world.player(runner.id).get_run_value();
                     if world.player(runner.id).mods.has(Mod::FreeRefill) {
                         self.outs -= 1;
@@ -250,27 +353,63 @@ impl Game {
             }
             //run multipliers and sun wackiness here
             self.scoreboard.batting_team_mut().score += runs_scored;
+            self.charge_runs(runners_scored);
         }
+        runners_scored
+    }
+
+    //rbis are counted in runners, not runs, so wired/tired and sun stuff don't change them.
+    //double plays, steals and mild pitches don't call this
+    fn credit_rbis(&mut self, rbis: u8) {
+        if rbis > 0 {
+            *self.rbis.entry(self.batter().unwrap()).or_insert(0) += rbis;
+        }
+    }
+
+    //the pitcher on the mound gets charged, there's no inherited runner bookkeeping
+    fn charge_runs(&mut self, runners: u8) {
+        if runners > 0 {
+            *self.runs_allowed.entry(self.pitcher()).or_insert(0) += runners;
+        }
+    }
+
+    fn credit_putout(&mut self, fielder: Uuid) {
+        *self.putouts.entry(fielder).or_insert(0) += 1;
+    }
+
+    fn credit_strikeout(&mut self) {
+        *self.strikeouts.entry(self.pitcher()).or_insert(0) += 1;
     }
     
+    fn shift_batter_index(&mut self, offset: isize) {
+        let bt = self.scoreboard.batting_team_mut();
+        bt.batter_index = bt.batter_index
+            .checked_add_signed(offset)
+            .expect("batter index went below the top of the lineup");
+    }
+
     fn end_pa(&mut self) {
         let bt = self.scoreboard.batting_team_mut();
         bt.batter = None;
-        bt.batter_index += 1;
+        //the lineup never had the ghost in it, so the haunted player is back next time through
+        bt.inhabited = None;
+        self.shift_batter_index(1);
         self.balls = 0;
         self.strikes = 0;
+        self.fouls = 0;
     }
 
     fn pick_fielder(&self, world: &World, roll: f64) -> Uuid {
         let pitching_team = world.team(self.scoreboard.pitching_team().id);
 
-        let idx = (roll * (pitching_team.lineup.len() as f64)).floor() as usize;
+        let idx = rng::index(roll, pitching_team.lineup.len());
         pitching_team.lineup[idx]
     }
 
     //might turn this into a more general function later
     //in place of an official incin target algorithm this might do
-    fn pick_player_weighted(&self, world: &World, roll: f64, weight: impl Fn(&Uuid) -> bool, only_current: bool) -> Uuid {
+    //None if the filter leaves nobody. the roll's already been used either way
+    fn pick_player_weighted(&self, world: &World, roll: f64, weight: impl Fn(&Uuid) -> bool, only_current: bool) -> Option<Uuid> {
         let home_team = world.team(self.scoreboard.home_team.id);
         let away_team = world.team(self.scoreboard.away_team.id);
 
@@ -294,40 +433,228 @@ impl Game {
         }
 
         eligible_players.retain(weight);
-        let idx = (roll * eligible_players.len() as f64).floor() as usize;
-        eligible_players[idx]
+        if eligible_players.is_empty() {
+            return None;
+        }
+        let idx = rng::index(roll, eligible_players.len());
+        Some(eligible_players[idx])
     }
 
-    pub fn get_run_value(&self) -> f64 {
+    //what one runner coming home is worth before their own mods:
+    //base * polarity * sun point one + sum sun, where base is 1, or 4/bases
+    //if the ruleset scales runs by base count (see Ruleset::scale_runs_by_bases)
+    pub fn get_run_value(&self, world: &World) -> f64 {
+        let base_value = if world.ruleset.scale_runs_by_bases { 4.0 / self.get_bases(world) as f64 } else { 1.0 };
         let polarity_coeff = if self.polarity { -1.0 } else { 1.0 };
         let sun_point_one_coeff = if let Weather::SunPointOne = self.weather { (self.inning as f64) / 10.0 } else { 1.0 };
         let sum_sun_coeff = if let Weather::SumSun = self.weather { self.scoring_plays_inning as f64 } else { 0.0 };
-        1.0 * polarity_coeff * sun_point_one_coeff + sum_sun_coeff
+        base_value * polarity_coeff * sun_point_one_coeff + sum_sun_coeff
+    }
+
+    pub fn triple_threat_active(&self, world: &World) -> bool {
+        world.player(self.pitcher()).mods.has(Mod::TripleThreat)
+            && (self.balls == 3
+                || self.runners.occupied(2)
+                || self.runners.len() == 3)
+    }
+
+    //every flat score adjustment that could hit the batting team right now,
+    //as (player, mod, runs per activation). the base run value isn't in here
+    pub fn scoring_modifiers(&self, world: &World) -> Vec<(Uuid, Mod, f64)> {
+        let mut modifiers = Vec::new();
+        let on_field = self.batter().into_iter().chain(self.runners.iter().map(|r| r.id));
+        for id in on_field {
+            let mods = &world.player(id).mods;
+            if mods.has(Mod::Wired) {
+                modifiers.push((id, Mod::Wired, WIRED_BONUS));
+            } else if mods.has(Mod::Tired) {
+                modifiers.push((id, Mod::Tired, TIRED_PENALTY));
+            }
+            //batters can't steal or get swept off the bases
+            if self.runners.contains(id) {
+                if mods.has(Mod::Blaserunning) {
+                    modifiers.push((id, Mod::Blaserunning, BLASERUNNING_BONUS));
+                }
+                if mods.has(Mod::Flippers) {
+                    modifiers.push((id, Mod::Flippers, FLIPPERS_BONUS));
+                }
+            }
+        }
+        if self.triple_threat_active(world) && world.ruleset.triple_threat_penalty {
+            modifiers.push((self.pitcher(), Mod::TripleThreat, TRIPLE_THREAT_PENALTY));
+        }
+        modifiers
+    }
+
+    pub fn trace_rolls(&mut self) {
+        self.rng_trace = Some(RefCell::new(Vec::new()));
+    }
+
+    //passes the roll through, noting it down first if tracing's on
+    pub fn trace_roll(&self, label: &str, roll: f64) -> f64 {
+        if let Some(trace) = &self.rng_trace {
+            trace.borrow_mut().push((String::from(label), roll));
+        }
+        roll
+    }
+
+    //called after every event. only a change in who's leading changes the pitchers of record,
+    //pitching changes while a lead holds don't
+    pub fn update_pitchers_of_record(&mut self) {
+        let lead = self.lead();
+        if let Ordering::Equal = lead {
+            self.pitchers_of_record = None;
+        } else if self.pitchers_of_record.is_none_or(|(prev, _, _)| prev != lead) {
+            self.pitchers_of_record = Some((lead, self.scoreboard.home_team.pitcher, self.scoreboard.away_team.pitcher));
+        }
+    }
+
+    //None until the game's over, or if it somehow ended tied
+    pub fn result(&self) -> Option<GameResult> {
+        if self.events.total() == 0 || self.events.last() != "GameOver" {
+            return None;
+        }
+        let (lead, home_pitcher, away_pitcher) = self.pitchers_of_record?;
+        let home = &self.scoreboard.home_team;
+        let away = &self.scoreboard.away_team;
+        Some(if let Ordering::Greater = lead {
+            GameResult {
+                winning_team: home.id,
+                losing_team: away.id,
+                winning_score: home.score,
+                losing_score: away.score,
+                winning_pitcher: home_pitcher,
+                losing_pitcher: away_pitcher,
+            }
+        } else {
+            GameResult {
+                winning_team: away.id,
+                losing_team: home.id,
+                winning_score: away.score,
+                losing_score: home.score,
+                winning_pitcher: away_pitcher,
+                losing_pitcher: home_pitcher,
+            }
+        })
+    }
+
+    //for crediting a reliever: the pitching team's ahead in the last inning (or extras)
+    //by three runs or less, or by few enough that the tying run's on base, up or on deck
+    pub fn is_save_situation(&self, world: &World) -> bool {
+        if self.inning < self.get_max_innings(world) {
+            return false;
+        }
+        let lead = self.scoreboard.pitching_team().score - self.scoreboard.batting_team().score;
+        if lead < SCORE_EPSILON {
+            return false;
+        }
+        let tying_run_due = (self.runners.len() + 2) as f64;
+        lead <= 3.0 + SCORE_EPSILON || lead <= tying_run_due + SCORE_EPSILON
+    }
+
+    //the score as a whole number for showing to people, floored like blaseball did.
+    //the real score stays a float, don't use this for game logic
+    pub fn display_score(&self, home: bool) -> i64 {
+        let score = if home { self.scoreboard.home_team.score } else { self.scoreboard.away_team.score };
+        score.floor() as i64
+    }
+
+    //how the home team stands against the away team:
+    //Greater if home is winning, Less if away is winning
+    pub fn lead(&self) -> Ordering {
+        let diff = self.scoreboard.home_team.score - self.scoreboard.away_team.score;
+        if diff.abs() < SCORE_EPSILON {
+            Ordering::Equal
+        } else if diff > 0.0 {
+            Ordering::Greater
+        } else {
+            Ordering::Less
+        }
+    }
+
+    //rough leverage index, 1.0 being a run of the mill plate appearance
+    //there's no win expectancy model yet so this scales
+    //base-out run expectancy by how late and how close the game is
+    pub fn leverage(&self, world: &World) -> f64 {
+        //MLB base-out run expectancy, indexed by outs and then
+        //occupied bases as a bitmask (1 = first, 2 = second, 4 = third)
+        const RUN_EXPECTANCY: [[f64; 8]; 3] = [
+            [0.48, 0.86, 1.10, 1.44, 1.35, 1.78, 1.96, 2.29],
+            [0.25, 0.51, 0.66, 0.89, 0.95, 1.13, 1.38, 1.54],
+            [0.10, 0.22, 0.32, 0.43, 0.35, 0.48, 0.57, 0.75],
+        ];
+        const AVERAGE_RUN_EXPECTANCY: f64 = 0.5;
+
+        //maintenance mode innings count their outs from the end
+        let outs_left = self.scoreboard.batting_team().max_outs - self.outs;
+        let outs = (3 - outs_left).clamp(0, 2) as usize;
+        //only the three bases closest to home count, so fifth base
+        //leagues don't get a free leverage boost
+        let bases = self.get_bases(world);
+        let mut state = 0;
+        for (bit, base) in (bases.saturating_sub(4)..bases - 1).enumerate() {
+            if self.runners.occupied(base) {
+                state |= 1 << bit;
+            }
+        }
+        let run_potential = RUN_EXPECTANCY[outs][state] / AVERAGE_RUN_EXPECTANCY;
+
+        let max_innings = self.get_max_innings(world);
+        let lateness = 0.5 + 1.5 * (self.inning.min(max_innings) as f64) / (max_innings as f64);
+        let margin = (self.scoreboard.home_team.score - self.scoreboard.away_team.score).abs();
+        let closeness = (-margin / 2.0).exp();
+
+        run_potential * lateness * closeness
     }
 
     //todo: just pass in a mods vec
     pub fn get_max_strikes(&self, world: &World) -> i16 {
-        let batter = world.player(self.scoreboard.batting_team().batter.unwrap());
-        let team = world.team(self.scoreboard.batting_team().id);
-        if batter.mods.has(Mod::FourthStrike) || team.mods.has(Mod::FourthStrike) {
+        let batting_team = self.scoreboard.batting_team();
+        if world.player_or_team_has(batting_team.batter.unwrap(), batting_team.id, Mod::FourthStrike) {
             4
         } else {
             3
         }
     }
 
+    //everything that decides when a ball is ball four goes through here.
+    //no ruleset changes it, only WalkInThePark on the batter or their team
     pub fn get_max_balls(&self, world: &World) -> i16 {
-        let batter = world.player(self.scoreboard.batting_team().batter.unwrap());
-        let team = world.team(self.scoreboard.batting_team().id);
-        if batter.mods.has(Mod::WalkInThePark) || team.mods.has(Mod::WalkInThePark) {
+        let batting_team = self.scoreboard.batting_team();
+        if world.player_or_team_has(batting_team.batter.unwrap(), batting_team.id, Mod::WalkInThePark) {
             3
         } else {
             4
         }
     }
 
+    pub fn get_max_innings(&self, world: &World) -> i16 {
+        world.innings
+    }
+
+    //the batting team's count, so it can change between halves.
+    //InningSwitch resizes the runners after flipping sides
     pub fn get_bases(&self, world: &World) -> u8 {
-        if world.team(self.scoreboard.batting_team().id).mods.has(Mod::FifthBase) {
+        Game::team_bases(self.scoreboard.batting_team().id, world)
+    }
+
+    //whoever's turn it is in the rotation, unless they're shelled or elsewhere,
+    //then the next one along who can pitch. if nobody can, the scheduled one goes anyway
+    fn starting_pitcher(team: Uuid, day: usize, world: &World) -> Uuid {
+        let rotation = &world.team(team).rotation;
+        let scheduled = day % rotation.len();
+        (0..rotation.len())
+            .map(|i| rotation[(scheduled + i) % rotation.len()])
+            .find(|&id| {
+                let mods = &world.player(id).mods;
+                !mods.has_any(&[Mod::Shelled, Mod::Elsewhere])
+            })
+            .unwrap_or(rotation[scheduled])
+    }
+
+    //todo: ballparks can add bases too, once stadiums have anything in them
+    fn team_bases(team: Uuid, world: &World) -> u8 {
+        if world.team(team).mods.has(Mod::FifthBase) {
             5
         } else {
             4
@@ -338,10 +665,55 @@ impl Game {
         self.scoreboard.batting_team().batter
     }
 
+    //for an ai or a person calling the shots: put the batter on instead of pitching.
+    //goes in before the next tick, applied like any other walk (so it forces runs in).
+    //hands back the event for logging
+    pub fn intentional_walk(&mut self, world: &mut World) -> Event {
+        assert!(self.batter().is_some(), "nobody's up to walk");
+        let event = Event::IntentionalWalk;
+        event.apply(self, world);
+        event
+    }
+
+    //who the pitcher has to get through, in lineup order.
+    //unlike effective_lineup this keeps everyone, skipped or not
+    pub fn opposing_lineup<'a>(&self, world: &'a World) -> Vec<&'a Player> {
+        world.team(self.scoreboard.batting_team().id).lineup
+            .iter()
+            .map(|&id| world.player(id))
+            .collect()
+    }
+
+    //the batting order as it actually comes up:
+    //Elsewhere and Shelled players get skipped by BatterStatePlugin
+    pub fn effective_lineup(&self, world: &World) -> Vec<Uuid> {
+        world.team(self.scoreboard.batting_team().id).lineup
+            .iter()
+            .filter(|&&id| {
+                let mods = &world.player(id).mods;
+                !mods.has_any(&[Mod::Elsewhere, Mod::Shelled])
+            })
+            .copied()
+            .collect()
+    }
+
+    //for mods that only trigger before anything's been thrown.
+    //a count zapped or siphoned back to 0-0 still counts,
+    //same as the charm/magmatic checks always did
+    pub fn is_first_pitch(&self) -> bool {
+        self.balls == 0 && self.strikes == 0
+    }
+
     pub fn assign_batter(&mut self, new: Uuid) {
         self.scoreboard.batting_team_mut().batter = Some(new);
     }
 
+    //how far along the game is, in pitches thrown (see Event::is_pitch).
+    //for anything that needs a clock finer than the inning
+    pub fn pitch_count(&self) -> u32 {
+        self.pitches
+    }
+
     pub fn pitcher(&self) -> Uuid {
         self.scoreboard.pitching_team().pitcher
     }
@@ -367,7 +739,8 @@ impl Game {
     }*/
 
     pub fn update_multiplier_data(&mut self, world: &World) {
-        if self.multiplier_data.top != self.scoreboard.top {
+        let switched = self.multiplier_data.top != self.scoreboard.top;
+        if switched {
             self.multiplier_data.top = self.scoreboard.top;
             self.multiplier_data.batting_team_mods = world.team(self.scoreboard.pitching_team().id).mods.clone();
             self.multiplier_data.pitching_team_mods = world.team(self.scoreboard.batting_team().id).mods.clone();
@@ -377,6 +750,9 @@ impl Game {
         }
         self.multiplier_data.runners_empty = self.runners.empty();
         self.multiplier_data.maximum_blaseball = self.runners.iter().count() == 3; //todo: kid named fifth base
+        //right after the sides switch the mods above are still the old sides',
+        //so the next event has to recompute whatever it is
+        self.multiplier_data_dirty = switched;
     }
 }
 
@@ -413,3 +789,381 @@ impl Scoreboard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::ModLifetime;
+    use crate::scenario::{test_world, GameScenario};
+
+    //away's leadoff hitter up in the top of the first
+    fn max_balls(world: &mut World, rng: &mut Rng, home: Uuid, away: Uuid) -> i16 {
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(world, rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        game.get_max_balls(world)
+    }
+
+    #[test]
+    fn max_balls_default() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        assert_eq!(max_balls(&mut world, &mut rng, home, away), 4);
+    }
+
+    #[test]
+    fn max_balls_walk_in_the_park_batter() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        world.give_mod(batter, Mod::WalkInThePark, ModLifetime::Permanent);
+        assert_eq!(max_balls(&mut world, &mut rng, home, away), 3);
+    }
+
+    #[test]
+    fn max_balls_walk_in_the_park_team() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        world.team_mut(away).mods.add(Mod::WalkInThePark, ModLifetime::Permanent);
+        assert_eq!(max_balls(&mut world, &mut rng, home, away), 3);
+    }
+
+    //only the batting side counts
+    #[test]
+    fn max_balls_walk_in_the_park_pitching_team() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        world.team_mut(home).mods.add(Mod::WalkInThePark, ModLifetime::Permanent);
+        assert_eq!(max_balls(&mut world, &mut rng, home, away), 4);
+    }
+
+    #[test]
+    fn max_balls_same_every_season() {
+        for season in [0, 11, 16, 20] {
+            let (mut world, mut rng, home, away) = test_world(season);
+            assert_eq!(max_balls(&mut world, &mut rng, home, away), 4);
+            world.team_mut(away).mods.add(Mod::WalkInThePark, ModLifetime::Permanent);
+            assert_eq!(max_balls(&mut world, &mut rng, home, away), 3);
+        }
+    }
+
+    //BasePlugin's walk goes off get_max_balls and nothing else
+    #[test]
+    fn walk_in_the_park_walks_on_ball_three() {
+        use crate::sim::{outcome_event, PitchOutcome};
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        game.balls = 2;
+        assert!(matches!(outcome_event(PitchOutcome::Ball, &game, &world, &mut rng), Event::Ball));
+
+        world.team_mut(away).mods.add(Mod::WalkInThePark, ModLifetime::Permanent);
+        assert!(matches!(outcome_event(PitchOutcome::Ball, &game, &world, &mut rng), Event::Walk));
+    }
+
+    //nobody comes out of the game, so the starters get the decisions
+    #[test]
+    fn complete_game_starter_gets_the_win() {
+        use crate::sim::Sim;
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let home_starter = game.scoreboard.home_team.pitcher;
+        let away_starter = game.scoreboard.away_team.pitcher;
+        assert!(game.result().is_none());
+        let mut sim = Sim::new(&mut world, &mut rng);
+        let result = sim.run_game_stats_only(&mut game);
+        if result.winning_team == home {
+            assert_eq!((result.winning_pitcher, result.losing_pitcher), (home_starter, away_starter));
+        } else {
+            assert_eq!((result.winning_pitcher, result.losing_pitcher), (away_starter, home_starter));
+        }
+    }
+
+    #[test]
+    fn tied_game_has_no_result() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        Event::GameOver.apply(&mut game, &mut world);
+        assert!(game.result().is_none());
+    }
+
+    //bases loaded, so the walk forces exactly the runner from third in
+    #[test]
+    fn intentional_walk_bases_loaded_forces_one_run() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .runner(2, lineup[1])
+            .runner(1, lineup[2])
+            .runner(0, lineup[3])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+
+        assert!(matches!(game.intentional_walk(&mut world), Event::IntentionalWalk));
+        assert_eq!(game.scoreboard.away_team.score, 1.0);
+        assert_eq!(game.runners.occupants(), vec![lineup[2], lineup[3], lineup[0]]);
+        assert_eq!(game.rbis.get(&lineup[0]), Some(&1));
+    }
+
+    //Game isn't PartialEq, so it's compared through Debug, which covers every field
+    #[test]
+    fn reset_matches_a_fresh_game() {
+        use crate::sim::Sim;
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        sim.run_game_stats_only(&mut game);
+        assert!(game.events.total() > 0);
+
+        game.reset(away, home, 3, Some(Weather::Eclipse), &world, &mut Rng::new(1, 2));
+        let fresh = Game::new(away, home, 3, Some(Weather::Eclipse), &world, &mut Rng::new(1, 2));
+        assert_eq!(format!("{:?}", game), format!("{:?}", fresh));
+    }
+
+    //one player elsewhere: the away team bats around them, everyone else in order
+    #[test]
+    fn lineup_bats_around_an_elsewhere_player() {
+        use crate::sim::Sim;
+        let (mut world, mut rng, home, away) = test_world(12);
+        let gone = world.team(away).lineup[3];
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .player_mod(gone, Mod::Elsewhere, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        let effective = game.effective_lineup(&world);
+        assert_eq!(effective.len(), 8);
+        assert!(!effective.contains(&gone));
+
+        let mut sim = Sim::new(&mut world, &mut rng);
+        let mut batters = Vec::new();
+        while batters.len() < 20 {
+            let evt = sim.next(&game);
+            if let Event::BatterUp { batter } = evt {
+                if game.scoreboard.top {
+                    batters.push(batter);
+                }
+            }
+            evt.apply(&mut game, sim.world);
+        }
+        for (i, batter) in batters.iter().enumerate() {
+            assert_eq!(*batter, effective[i % effective.len()]);
+        }
+    }
+
+    //bases loaded, two out, tied in the 9th vs the same spot in a blowout
+    #[test]
+    fn leverage_high_stakes_vs_blowout() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .runner(2, lineup[1])
+            .runner(1, lineup[2])
+            .runner(0, lineup[3])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        game.inning = 9;
+        game.outs = 2;
+        let clutch = game.leverage(&world);
+        assert!(clutch > 2.0, "{}", clutch);
+
+        game.scoreboard.home_team.score = 12.0;
+        let blowout = game.leverage(&world);
+        assert!(blowout < 0.1, "{}", blowout);
+
+        //and an empty first inning is about average
+        let fresh = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let leadoff = fresh.leverage(&world);
+        assert!(leadoff > 0.5 && leadoff < 1.5, "{}", leadoff);
+    }
+
+
+    #[test]
+    fn first_pitch_until_anything_is_thrown() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        for pitch in [Event::Ball, Event::Strike, Event::Foul] {
+            let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+            game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+            assert!(game.is_first_pitch());
+            pitch.apply(&mut game, &mut world);
+            assert!(!game.is_first_pitch());
+        }
+    }
+
+
+    //a bigger crowd is a bigger home field run, and the one already on the board gets swapped out
+    #[test]
+    fn crowd_scales_home_field_advantage() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .team_mod(home, Mod::HomeFieldAdvantage, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        assert_eq!(game.home_field_bonus(&world), 1.0);
+        assert_eq!(game.scoreboard.home_team.score, 1.0);
+
+        game.set_crowd(2.0, &world);
+        assert_eq!(game.home_field_bonus(&world), 2.0);
+        assert_eq!(game.scoreboard.home_team.score, 2.0);
+        assert_eq!(game.linescore_home[0], 2.0);
+
+        game.set_crowd(0.5, &world);
+        assert_eq!(game.scoreboard.home_team.score, 0.5);
+
+        //no advantage, no bonus, however many turn up
+        let mut game = GameScenario::new(away, home).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.set_crowd(2.0, &world);
+        assert_eq!(game.home_field_bonus(&world), 0.0);
+        assert_eq!(game.scoreboard.home_team.score, 0.0);
+    }
+
+
+    //a blaserunner counts once they're on base, not while they're batting
+    #[test]
+    fn scoring_modifiers_lists_blaserunning() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        world.give_mod(lineup[1], Mod::Blaserunning, ModLifetime::Permanent);
+
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[1]);
+        assert!(game.scoring_modifiers(&world).is_empty());
+
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .runner(0, lineup[1])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        assert_eq!(game.scoring_modifiers(&world), vec![(lineup[1], Mod::Blaserunning, 0.2)]);
+        assert_eq!(BLASERUNNING_BONUS, 0.2);
+    }
+
+
+    #[test]
+    fn display_score_floors() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.home_team.score = 3.2;
+        game.scoreboard.away_team.score = -0.3;
+        assert_eq!(game.display_score(true), 3);
+        assert_eq!(game.display_score(false), -1);
+        assert_eq!(game.scoreboard.home_team.score, 3.2);
+        assert_eq!(game.scoreboard.away_team.score, -0.3);
+    }
+
+
+    //home pitching in the top of the inning
+    #[test]
+    fn save_situation() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.inning = 9;
+        game.scoreboard.home_team.score = 4.0;
+        game.scoreboard.away_team.score = 3.0;
+        assert!(game.is_save_situation(&world));
+
+        game.scoreboard.home_team.score = 13.0;
+        assert!(!game.is_save_situation(&world));
+
+        //tied or behind isn't a save, and neither is a close game before the 9th
+        game.scoreboard.home_team.score = 3.0;
+        assert!(!game.is_save_situation(&world));
+        game.scoreboard.home_team.score = 4.0;
+        game.inning = 8;
+        assert!(!game.is_save_situation(&world));
+    }
+
+
+    //only the away team has a fifth base, so the count flips with the sides
+    #[test]
+    fn fifth_base_flips_between_halves() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .team_mod(away, Mod::FifthBase, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        assert_eq!(game.get_bases(&world), 5);
+        assert_eq!(game.runners.base_number, 5);
+
+        Event::InningSwitch { inning: 1, top: false, runs_this_half: 0.0 }.apply(&mut game, &mut world);
+        assert_eq!(game.get_bases(&world), 4);
+        assert_eq!(game.runners.base_number, 4);
+
+        Event::InningSwitch { inning: 2, top: true, runs_this_half: 0.0 }.apply(&mut game, &mut world);
+        assert_eq!(game.get_bases(&world), 5);
+        assert_eq!(game.runners.base_number, 5);
+    }
+
+
+    //from the home pitcher's side in the top of the inning: away's lineup, in order, and the other way round in the bottom
+    #[test]
+    fn opposing_lineup_in_order() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let ids = |game: &Game, world: &World| game.opposing_lineup(world).iter().map(|p| p.id).collect::<Vec<_>>();
+        assert_eq!(ids(&game, &world), world.team(away).lineup);
+        game.scoreboard.top = false;
+        assert_eq!(ids(&game, &world), world.team(home).lineup);
+    }
+
+
+    //a solo shot is worth a run with four bases, and 4/5 of one with five if the ruleset scales by bases
+    #[test]
+    fn solo_home_run_by_base_count() {
+        let solo = |fifth_base: bool, scale: bool| {
+            let (mut world, mut rng, home, away) = test_world(12);
+            world.ruleset.scale_runs_by_bases = scale;
+            let mut scenario = GameScenario::new(home, away).weather(Weather::Sun);
+            if fifth_base {
+                scenario = scenario.team_mod(away, Mod::FifthBase, ModLifetime::Permanent);
+            }
+            let mut game = scenario.build(&mut world, &mut rng);
+            game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+            Event::HomeRun.apply(&mut game, &mut world);
+            game.scoreboard.away_team.score
+        };
+        assert_eq!(solo(false, true), 1.0);
+        assert_eq!(solo(true, true), 0.8);
+        assert_eq!(solo(false, false), 1.0);
+        assert_eq!(solo(true, false), 1.0);
+    }
+
+
+    //sum sun adds a run per scoring play already in this half, to every run scored:
+    //a solo shot (1), a two run shot (2 + 2), then a bases loaded walk (3) is 8
+    #[test]
+    fn sum_sun_multi_run_inning() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away).weather(Weather::SumSun).build(&mut world, &mut rng);
+
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        Event::HomeRun.apply(&mut game, &mut world);
+        assert_eq!((game.scoreboard.away_team.score, game.scoring_plays_inning), (1.0, 1));
+
+        game.runners.add(0, lineup[0]);
+        game.scoreboard.away_team.batter = Some(lineup[1]);
+        Event::HomeRun.apply(&mut game, &mut world);
+        assert_eq!((game.scoreboard.away_team.score, game.scoring_plays_inning), (5.0, 2));
+
+        game.runners.add(2, lineup[0]);
+        game.runners.add(1, lineup[1]);
+        game.runners.add(0, lineup[2]);
+        game.scoreboard.away_team.batter = Some(lineup[3]);
+        Event::Walk.apply(&mut game, &mut world);
+        assert_eq!((game.scoreboard.away_team.score, game.scoring_plays_inning), (8.0, 3));
+
+        //a fresh half starts back at nothing
+        Event::InningSwitch { inning: 1, top: false, runs_this_half: 8.0 }.apply(&mut game, &mut world);
+        assert_eq!(game.scoring_plays_inning, 0);
+        assert_eq!(game.get_run_value(&world), 1.0);
+    }
+
+
+    #[test]
+    fn ground_out_credits_the_fielder() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let fielder = world.team(home).lineup[5];
+        for batter in 0..2 {
+            game.scoreboard.away_team.batter = Some(world.team(away).lineup[batter]);
+            Event::GroundOut { fielder, runners_after: game.runners }.apply(&mut game, &mut world);
+        }
+        assert_eq!(game.putouts.get(&fielder), Some(&2));
+        assert_eq!(game.putouts.len(), 1);
+        assert_eq!(game.outs, 2);
+    }
+}
@@ -1,6 +1,19 @@
 use strum::EnumString;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+//flat score changes mods make, outside of the usual run value
+pub const BLASERUNNING_BONUS: f64 = 0.2; //per steal
+pub const FLIPPERS_BONUS: f64 = 1.0; //per runner swept elsewhere, on top of their run value
+pub const TRIPLE_THREAT_PENALTY: f64 = -0.3; //per strikeout
+pub const WIRED_BONUS: f64 = 0.5; //per run scored
+pub const TIRED_PENALTY: f64 = -0.5;
+
+//per stat on a bad peanut. the regular allergic reaction is -0.2, the mod text only says superallergic
+//players react worse and nobody's published the real number, so this one's an estimate
+pub const SUPERALLERGIC_PENALTY: f64 = -0.5;
+
+pub const REVERBERATING_LIMIT: u8 = 3; //re-bats per half-inning, so nobody bats forever
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumString)]
 // todo: repr u16 for compactness?
 #[strum(serialize_all="SCREAMING_SNAKE_CASE")]
 pub enum Mod {
@@ -87,8 +100,8 @@ pub enum Mod {
     Ambush
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ModLifetime {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ModLifetime { //shortest to longest
     Game,
     Week,
     Season,
@@ -118,33 +131,138 @@ impl Mods {
         self.mods.iter().any(|x| x.the_mod == m)
     }
 
-    pub fn add(&mut self, m: Mod, lifetime: ModLifetime) {
-        let ml = ModWithLifetime {
-            the_mod: m,
-            lifetime: lifetime,
-        };
-        if !self.mods.contains(&ml) {
-            self.mods.push(ml);
+    pub fn has_any(&self, ms: &[Mod]) -> bool {
+        ms.iter().any(|&m| self.has(m))
+    }
+
+    pub fn has_all(&self, ms: &[Mod]) -> bool {
+        ms.iter().all(|&m| self.has(m))
+    }
+
+    //a mod is only ever on here once, re-adding it just keeps whichever lifetime lasts longer.
+    //true if that changed anything, i.e. the mod is new or its lifetime went up
+    pub fn add(&mut self, m: Mod, lifetime: ModLifetime) -> bool {
+        if let Some(existing) = self.mods.iter_mut().find(|x| x.the_mod == m) {
+            if lifetime <= existing.lifetime {
+                return false;
+            }
+            existing.lifetime = lifetime;
+        } else {
+            self.mods.push(ModWithLifetime {
+                the_mod: m,
+                lifetime,
+            });
         }
+        true
     }
 
-    pub fn remove(&mut self, m: Mod) {
-        self.mods.retain(|x| x.the_mod != m)
+    //true if they had it
+    pub fn remove(&mut self, m: Mod) -> bool {
+        let before = self.mods.len();
+        self.mods.retain(|x| x.the_mod != m);
+        self.mods.len() != before
     }
 
-    pub fn clear_game(&mut self) {
-        self.mods.retain(|x| x.lifetime != ModLifetime::Game);
+    pub fn iter(&self) -> impl Iterator<Item = (Mod, ModLifetime)> + '_ {
+        self.mods.iter().map(|x| (x.the_mod, x.lifetime))
     }
 
-    pub fn clear_weekly(&mut self) {
-        self.mods.retain(|x| x.lifetime != ModLifetime::Week);
+    pub fn get_lifetime(&self, m: Mod) -> Option<ModLifetime> {
+        self.mods.iter().find(|x| x.the_mod == m).map(|x| x.lifetime)
     }
 
-    pub fn clear_season(&mut self) {
-        self.mods.retain(|x| x.lifetime != ModLifetime::Season);
+    //expired mods come back ordered by their position in the Mod enum,
+    //not by when they were added, so anything reacting to them is deterministic
+    fn clear_lifetime(&mut self, lifetime: ModLifetime) -> Vec<Mod> {
+        let mut expired: Vec<Mod> = self.mods
+            .iter()
+            .filter(|x| x.lifetime == lifetime)
+            .map(|x| x.the_mod)
+            .collect();
+        expired.sort();
+        self.mods.retain(|x| x.lifetime != lifetime);
+        expired
+    }
+
+    pub fn clear_game(&mut self) -> Vec<Mod> {
+        self.clear_lifetime(ModLifetime::Game)
+    }
+
+    pub fn clear_weekly(&mut self) -> Vec<Mod> {
+        self.clear_lifetime(ModLifetime::Week)
+    }
+
+    pub fn clear_season(&mut self) -> Vec<Mod> {
+        self.clear_lifetime(ModLifetime::Season)
     }
 
     pub fn clear_legendary_item(&mut self) {
         self.mods.retain(|x| x.lifetime != ModLifetime::LegendaryItem);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_any_and_has_all() {
+        let mut mods = Mods::new();
+        mods.add(Mod::Fireproof, ModLifetime::Permanent);
+        mods.add(Mod::Electric, ModLifetime::Game);
+
+        assert!(mods.has_any(&[Mod::Fireproof, Mod::Sealant]));
+        assert!(!mods.has_any(&[Mod::Sealant, Mod::Flinch]));
+        assert!(!mods.has_any(&[]));
+
+        assert!(mods.has_all(&[Mod::Fireproof, Mod::Electric]));
+        assert!(!mods.has_all(&[Mod::Fireproof, Mod::Sealant]));
+        assert!(mods.has_all(&[]));
+
+        assert!(!Mods::new().has_any(&[Mod::Fireproof]));
+    }
+
+    #[test]
+    fn add_and_remove_report_real_changes() {
+        let mut mods = Mods::new();
+        assert!(mods.add(Mod::Fireproof, ModLifetime::Week));
+        assert!(!mods.add(Mod::Fireproof, ModLifetime::Week));
+        assert!(!mods.add(Mod::Fireproof, ModLifetime::Game));
+        assert!(mods.add(Mod::Fireproof, ModLifetime::Permanent));
+        assert!(mods.remove(Mod::Fireproof));
+        assert!(!mods.remove(Mod::Fireproof));
+    }
+
+    #[test]
+    fn re_adding_keeps_one_with_the_longer_lifetime() {
+        let mut mods = Mods::new();
+        mods.add(Mod::Fireproof, ModLifetime::Week);
+        mods.add(Mod::Fireproof, ModLifetime::Permanent);
+        assert_eq!(mods.iter().collect::<Vec<_>>(), vec![(Mod::Fireproof, ModLifetime::Permanent)]);
+
+        //and going back down doesn't shorten it
+        mods.add(Mod::Fireproof, ModLifetime::Week);
+        assert_eq!(mods.iter().collect::<Vec<_>>(), vec![(Mod::Fireproof, ModLifetime::Permanent)]);
+    }
+
+
+    #[test]
+    fn iter_and_get_lifetime() {
+        let mut mods = Mods::new();
+        mods.add(Mod::Fireproof, ModLifetime::Permanent);
+        mods.add(Mod::Electric, ModLifetime::Game);
+        mods.add(Mod::Sealant, ModLifetime::Season);
+        mods.add(Mod::Flinch, ModLifetime::Week);
+
+        let mut all: Vec<_> = mods.iter().collect();
+        all.sort_by_key(|&(_, lifetime)| lifetime);
+        assert_eq!(all, vec![
+            (Mod::Electric, ModLifetime::Game),
+            (Mod::Flinch, ModLifetime::Week),
+            (Mod::Sealant, ModLifetime::Season),
+            (Mod::Fireproof, ModLifetime::Permanent),
+        ]);
+        assert_eq!(mods.get_lifetime(Mod::Sealant), Some(ModLifetime::Season));
+        assert_eq!(mods.get_lifetime(Mod::Wired), None);
+    }
+}
@@ -1,11 +1,12 @@
 pub struct Rng {
     s0: u64,
     s1: u64,
+    draws: u64, //how many numbers have been pulled, see frozen
 }
 
 impl Rng {
     pub fn new(s0: u64, s1: u64) -> Rng {
-        Rng { s0, s1 }
+        Rng { s0, s1, draws: 0 }
     }
 
     fn step(&mut self) {
@@ -19,13 +20,72 @@ impl Rng {
         self.s1 = s1;
     }
 
+    //always in [0, 1)
     pub fn next(&mut self) -> f64 {
         self.step();
+        self.draws += 1;
 
         f64::from_bits((self.s0 >> 12) | 0x3FF0000000000000) - 1.0
     }
 
+    //a uniform pick out of len things
     pub fn index(&mut self, len: usize) -> usize {
-        (self.next() * len as f64).floor() as usize
+        index(self.next(), len)
+    }
+
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+
+    //for previews and anything else that's only supposed to look:
+    //runs f and (in debug builds) panics if it rolled anything,
+    //since an extra roll throws every game after it out of sync
+    pub fn frozen<T>(&mut self, f: impl FnOnce(&mut Rng) -> T) -> T {
+        let before = self.draws;
+        let result = f(self);
+        debug_assert_eq!(self.draws, before, "rng used during a preview");
+        result
+    }
+}
+
+//Rng::index for a roll that's already been made (e.g. through Game::trace_roll).
+//next never gives 1.0 but the clamp means an index from here is always in bounds anyway
+pub fn index(roll: f64, len: usize) -> usize {
+    ((roll * len as f64).floor() as usize).min(len.saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "rng used during a preview")]
+    fn frozen_catches_a_roll() {
+        let mut rng = Rng::new(69, 420);
+        rng.frozen(|rng| rng.next());
+    }
+
+    #[test]
+    fn frozen_passes_through() {
+        let mut rng = Rng::new(69, 420);
+        rng.next();
+        assert_eq!(rng.frozen(|rng| rng.draws()), 1);
+        assert_eq!(rng.draws(), 1);
+    }
+
+    #[test]
+    fn index_stays_in_bounds() {
+        for len in [1, 2, 3, 7, 100] {
+            assert_eq!(index(0.0, len), 0);
+            assert_eq!(index(1.0, len), len - 1);
+            assert_eq!(index(1.0 - f64::EPSILON, len), len - 1);
+        }
+        assert_eq!(index(0.5, 4), 2);
+
+        let mut rng = Rng::new(69, 420);
+        for _ in 0..10000 {
+            assert!(rng.index(3) < 3);
+        }
     }
 }
@@ -0,0 +1,117 @@
+//rules that differ between eras (or that someone wants to turn off),
+//looked up by name instead of comparing season numbers inline
+#[derive(Clone, Copy, Debug)]
+pub struct Ruleset {
+    pub season: u8,
+    pub triple_threat_penalty: bool, //strikeouts under an active triple threat cost the batting team runs
+    //not a blaseball thing, fifth base runs were worth the same as any other.
+    //on, a run is worth 4/bases, so each base run counts the same as in a four base league
+    pub scale_runs_by_bases: bool,
+    //not a blaseball thing either. the real game doesn't roll to swing for a flinching batter on strike 0,
+    //on, it does, against swing_threshold's near-zero chance. costs a roll, so off keeps the rng in sync
+    pub flinch_swings: bool,
+
+    //per-tick chances, worked out from the season in new
+    pub charm: Threshold, //by mysticism
+    pub blooddrain: Threshold, //by fortification
+    pub flooding: Threshold, //by fortification
+    pub party: f64,
+    pub elsewhere_return: f64,
+    pub unscatter: f64,
+}
+
+//a chance that moves with one stat, like fortification or mysticism
+#[derive(Clone, Copy, Debug)]
+pub struct Threshold {
+    pub base: f64,
+    pub per_stat: f64,
+}
+
+impl Threshold {
+    pub fn at(&self, stat: f64) -> f64 {
+        self.base + self.per_stat * stat
+    }
+}
+
+impl Ruleset {
+    pub fn new(season: u8) -> Ruleset {
+        let charm = if season == 18 {
+            Threshold { base: 0.014, per_stat: 0.006 }
+        } else {
+            Threshold { base: 0.015, per_stat: 0.02 }
+        };
+        let blooddrain = if season < 16 {
+            Threshold { base: 0.00065, per_stat: -0.001 }
+        } else {
+            Threshold { base: 0.00125, per_stat: -0.00125 }
+        };
+        let flooding = match season {
+            11..14 => Threshold { base: 0.019, per_stat: -0.02 },
+            14..17 => Threshold { base: 0.013, per_stat: -0.012 },
+            17 => Threshold { base: 0.015, per_stat: -0.012 },
+            18..24 => Threshold { base: 0.016, per_stat: -0.012 },
+            _ => Threshold { base: 0.0, per_stat: 0.0 },
+        };
+        let elsewhere_return = match season {
+            11 => 0.001,
+            12 => 0.000575,
+            13..18 => 0.0004,
+            18..24 => 0.00035,
+            _ => 0.0
+        };
+        let unscatter = match season {
+            11 | 12 => 0.00061,
+            13 => 0.0005,
+            14..17 => 0.0004,
+            17..20 => 0.00042,
+            20 | 21 => 0.000485,
+            22 | 23 => 0.000495,
+            _ => 0.0
+        };
+        Ruleset {
+            season,
+            triple_threat_penalty: true,
+            scale_runs_by_bases: false,
+            flinch_swings: false,
+            charm,
+            blooddrain,
+            flooding,
+            party: if season < 20 { 0.0055 } else { 0.00525 },
+            elsewhere_return,
+            unscatter,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //what the plugins used to work out inline from the season number
+    #[test]
+    fn thresholds_by_season() {
+        let s11 = Ruleset::new(11);
+        assert_eq!(s11.season, 11);
+        assert_eq!((s11.charm.base, s11.charm.per_stat), (0.015, 0.02));
+        assert_eq!((s11.blooddrain.base, s11.blooddrain.per_stat), (0.00065, -0.001));
+        assert_eq!((s11.flooding.base, s11.flooding.per_stat), (0.019, -0.02));
+        assert_eq!(s11.party, 0.0055);
+        assert_eq!(s11.elsewhere_return, 0.001);
+        assert_eq!(s11.unscatter, 0.00061);
+
+        let s16 = Ruleset::new(16);
+        assert_eq!((s16.blooddrain.base, s16.blooddrain.per_stat), (0.00125, -0.00125));
+        assert_eq!((s16.flooding.base, s16.flooding.per_stat), (0.013, -0.012));
+        assert_eq!(s16.elsewhere_return, 0.0004);
+        assert_eq!(s16.unscatter, 0.0004);
+
+        let s20 = Ruleset::new(20);
+        assert_eq!((s20.charm.base, s20.charm.per_stat), (0.015, 0.02));
+        assert_eq!((s20.flooding.base, s20.flooding.per_stat), (0.016, -0.012));
+        assert_eq!(s20.party, 0.00525);
+        assert_eq!(s20.elsewhere_return, 0.00035);
+        assert_eq!(s20.unscatter, 0.000485);
+
+        assert_eq!(Ruleset::new(18).charm.base, 0.014);
+    }
+}
@@ -0,0 +1,92 @@
+use uuid::Uuid;
+
+use crate::{entities::World, mods::{Mod, ModLifetime}, rng::Rng, Game, Weather};
+
+//a game set up just so, for poking at a specific interaction.
+//chain the setters and then build it into a Game
+#[derive(Clone, Debug)]
+pub struct GameScenario {
+    pub home_team: Uuid,
+    pub away_team: Uuid,
+    pub day: usize,
+    pub weather: Option<Weather>, //None rolls the weather like Game::new does
+    pub lineups: Vec<(Uuid, Vec<Uuid>)>, //team, lineup
+    pub player_mods: Vec<(Uuid, Mod, ModLifetime)>,
+    pub team_mods: Vec<(Uuid, Mod, ModLifetime)>,
+    pub runners: Vec<(u8, Uuid)>, //base, runner
+}
+
+impl GameScenario {
+    pub fn new(home_team: Uuid, away_team: Uuid) -> GameScenario {
+        GameScenario {
+            home_team,
+            away_team,
+            day: 0,
+            weather: None,
+            lineups: Vec::new(),
+            player_mods: Vec::new(),
+            team_mods: Vec::new(),
+            runners: Vec::new(),
+        }
+    }
+
+    pub fn day(mut self, day: usize) -> GameScenario {
+        self.day = day;
+        self
+    }
+
+    pub fn weather(mut self, weather: Weather) -> GameScenario {
+        self.weather = Some(weather);
+        self
+    }
+
+    pub fn lineup(mut self, team: Uuid, lineup: Vec<Uuid>) -> GameScenario {
+        self.lineups.push((team, lineup));
+        self
+    }
+
+    pub fn player_mod(mut self, player: Uuid, m: Mod, lifetime: ModLifetime) -> GameScenario {
+        self.player_mods.push((player, m, lifetime));
+        self
+    }
+
+    pub fn team_mod(mut self, team: Uuid, m: Mod, lifetime: ModLifetime) -> GameScenario {
+        self.team_mods.push((team, m, lifetime));
+        self
+    }
+
+    pub fn runner(mut self, base: u8, runner: Uuid) -> GameScenario {
+        self.runners.push((base, runner));
+        self
+    }
+
+    //rosters and mods go on the world before the game is made,
+    //so anything Game::new looks at (fifth base, home field advantage) sees them
+    pub fn build(&self, world: &mut World, rng: &mut Rng) -> Game {
+        for (team, lineup) in self.lineups.iter() {
+            world.team_mut(*team).lineup = lineup.clone();
+        }
+        for &(player, m, lifetime) in self.player_mods.iter() {
+            world.give_mod(player, m, lifetime);
+        }
+        for &(team, m, lifetime) in self.team_mods.iter() {
+            world.give_team_mod(team, m, lifetime);
+        }
+        let mut game = Game::new(self.home_team, self.away_team, self.day, self.weather, world, rng);
+        for &(base, runner) in self.runners.iter() {
+            game.runners.add(base, runner);
+        }
+        game
+    }
+}
+
+//two generated teams with reproducible ids, for tests to build scenarios on
+#[cfg(test)]
+pub(crate) fn test_world(season: u8) -> (World, Rng, Uuid, Uuid) {
+    let mut rng = Rng::new(69, 420);
+    let mut world = World::new(season);
+    world.deterministic_ids();
+    let home = world.gen_team(&mut rng, String::from("Home"), String::from("H"));
+    let away = world.gen_team(&mut rng, String::from("Away"), String::from("A"));
+    (world, rng, home, away)
+}
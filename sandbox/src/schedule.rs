@@ -0,0 +1,122 @@
+use std::str::FromStr;
+
+use uuid::Uuid;
+
+use crate::{entities::World, rng::Rng, Game, Weather};
+
+//days in a blaseball week, weekly mods wear off after the last one
+pub const DAYS_PER_WEEK: usize = 9;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Matchup {
+    pub home_team: Uuid,
+    pub away_team: Uuid,
+    pub weather: Option<Weather>, //None rolls the weather like Game::new does
+}
+
+//a slate of games that all happen on the same day
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    pub day: usize,
+    pub matchups: Vec<Matchup>,
+}
+
+impl Schedule {
+    pub fn new(day: usize) -> Schedule {
+        Schedule {
+            day,
+            matchups: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, home_team: Uuid, away_team: Uuid, weather: Option<Weather>) {
+        self.matchups.push(Matchup { home_team, away_team, weather });
+    }
+
+    pub fn len(&self) -> usize {
+        self.matchups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matchups.is_empty()
+    }
+}
+
+//everything it takes to replay one game, as a string that can be pasted around.
+//the recipient needs the same world (teams and players) for it to come out the same
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GameId {
+    pub seed: (u64, u64),
+    pub day: usize,
+    pub home_team: Uuid,
+    pub away_team: Uuid,
+    pub weather: Weather,
+}
+
+impl GameId {
+    //seed.day.home.away.weather, seed and teams in hex
+    pub fn encode(&self) -> String {
+        format!(
+            "{:016x}{:016x}.{}.{}.{}.{}",
+            self.seed.0,
+            self.seed.1,
+            self.day,
+            self.home_team.simple(),
+            self.away_team.simple(),
+            self.weather
+        )
+    }
+
+    //None if it's not something encode made
+    pub fn decode(id: &str) -> Option<GameId> {
+        let parts: Vec<&str> = id.split('.').collect();
+        if parts.len() != 5 || parts[0].len() != 32 {
+            return None;
+        }
+        Some(GameId {
+            seed: (
+                u64::from_str_radix(parts[0].get(..16)?, 16).ok()?,
+                u64::from_str_radix(parts[0].get(16..)?, 16).ok()?,
+            ),
+            day: parts[1].parse().ok()?,
+            home_team: Uuid::parse_str(parts[2]).ok()?,
+            away_team: Uuid::parse_str(parts[3]).ok()?,
+            weather: Weather::from_str(parts[4]).ok()?,
+        })
+    }
+
+    //the game and the rng to sim it with, as they were at the start
+    pub fn start(&self, world: &World) -> (Game, Rng) {
+        let mut rng = Rng::new(self.seed.0, self.seed.1);
+        let game = Game::new(self.home_team, self.away_team, self.day, Some(self.weather), world, &mut rng);
+        (game, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_id_round_trip() {
+        for weather in [Weather::Sun, Weather::Eclipse, Weather::BlackHole, Weather::Coffee3, Weather::SunPointOne] {
+            let id = GameId {
+                seed: (u64::MAX, 1),
+                day: 98,
+                home_team: Uuid::from_u128(1),
+                away_team: Uuid::from_u128(u128::MAX - 1),
+                weather,
+            };
+            assert_eq!(GameId::decode(&id.encode()), Some(id));
+        }
+    }
+
+    #[test]
+    fn game_id_decode_rejects_junk() {
+        assert_eq!(GameId::decode(""), None);
+        assert_eq!(GameId::decode("abc.1.2.3.Sun"), None);
+        let id = GameId { seed: (69, 420), day: 0, home_team: Uuid::nil(), away_team: Uuid::nil(), weather: Weather::Sun }.encode();
+        assert_eq!(GameId::decode(&id.replace("Sun", "Rain")), None);
+        assert_eq!(GameId::decode(&format!("{}.extra", id)), None);
+    }
+}
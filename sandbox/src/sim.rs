@@ -1,24 +1,51 @@
+use std::{cell::Cell, cmp::Ordering, collections::HashMap, rc::Rc};
+
 use uuid::Uuid;
 
-use crate::{entities::{World, Player}, events::Event, formulas, mods::{Mod, Mods}, rng::Rng, Game, Weather};
+use crate::{bases::RunnerSet, entities::{World, Player, ReverbType, StatCategory, Team}, events::Event, formulas, mods::{Mod, REVERBERATING_LIMIT}, rng::Rng, schedule::Matchup, Game, GameResult, Weather};
+
+//stealing and the pitch, the plugins at this priority and up always have an event
+pub const STEALING_PRIORITY: u32 = 90;
 
 pub trait Plugin {
     fn tick(&self, _game: &Game, _world: &World, _rng: &mut Rng) -> Option<Event> {
         None
     }
+
+    //plugins tick lowest first and the first event wins. the stock ones:
+    //pregame 0, inning state 10, inning events 20, batter state 30, weather 40,
+    //elsewhere 50, party 60, flooding 70, mods 80, stealing 90, the pitch itself 100.
+    //the default puts a custom plugin right before the pitch. ties go in the order they were added
+    fn priority(&self) -> u32 {
+        95
+    }
+
+    //a cheap check for whether tick could do anything this time, so Sim::next can skip it.
+    //only say false if tick wouldn't roll either, skipping a roll throws the rng out of line
+    fn applies(&self, _game: &Game, _world: &World) -> bool {
+        true
+    }
 }
 
 pub struct Sim<'a> {
     plugins: Vec<Box<dyn Plugin>>,
     pub world: &'a mut World,
     pub rng: &'a mut Rng,
+    last_pitch: Rc<Cell<Option<PitchDetail>>>, //shared with BasePlugin
+    //for fuzzing: panic with the game state once both teams' runs add up past this,
+    //nothing legit should get anywhere near it
+    pub max_total_runs: Option<f64>,
 }
 
 impl<'a> Sim<'a> {
     pub fn new(world: &'a mut World, rng: &'a mut Rng) -> Sim<'a> {
+        let last_pitch = Rc::new(Cell::new(None));
         Sim {
             world,
             rng,
+            last_pitch: last_pitch.clone(),
+            max_total_runs: None,
+            //already in priority order, add_plugin keeps it that way
             plugins: vec![
                 Box::new(PregamePlugin),
                 Box::new(InningStatePlugin),
@@ -30,12 +57,17 @@ impl<'a> Sim<'a> {
                 Box::new(FloodingPlugin),
                 Box::new(ModPlugin),
                 Box::new(StealingPlugin),
-                Box::new(BasePlugin),
+                Box::new(BasePlugin { last_pitch }),
             ],
         }
     }
     pub fn next(&mut self, game: &Game) -> Event {
+        self.check_total_runs(game);
+        self.last_pitch.set(None);
         for plugin in self.plugins.iter() {
+            if !plugin.applies(game, self.world) {
+                continue;
+            }
             if let Some(event) = plugin.tick(game, &self.world, &mut self.rng) {
                 return event;
             }
@@ -43,165 +75,452 @@ impl<'a> Sim<'a> {
 
         panic!("uhhh")
     }
+
+    //like next, but if the first event is independent (see Event::is_independent) the plugins
+    //after it still get to tick, and whatever independent events they come up with go in the same batch,
+    //e.g. birds and an elsewhere return. apply them in order.
+    //stealing and the pitch (STEALING_PRIORITY and up) always have something to say so they're left out,
+    //and a dependent event from the rest gets dropped, it'll come up again next tick.
+    //the real game is one event per tick, so this doesn't line up with its rng
+    pub fn next_batch(&mut self, game: &Game) -> Vec<Event> {
+        self.check_total_runs(game);
+        self.last_pitch.set(None);
+        let mut events: Vec<Event> = Vec::new();
+        for plugin in self.plugins.iter() {
+            if !plugin.applies(game, self.world) {
+                continue;
+            }
+            if !events.is_empty() && plugin.priority() >= STEALING_PRIORITY {
+                break;
+            }
+            if let Some(event) = plugin.tick(game, self.world, self.rng) {
+                let independent = event.is_independent();
+                if events.is_empty() || independent {
+                    events.push(event);
+                }
+                if !independent {
+                    break;
+                }
+            }
+        }
+
+        if events.is_empty() {
+            panic!("uhhh")
+        }
+        events
+    }
+
+    fn check_total_runs(&self, game: &Game) {
+        if let Some(max_total_runs) = self.max_total_runs {
+            let total_runs = game.scoreboard.home_team.score + game.scoreboard.away_team.score;
+            if total_runs > max_total_runs {
+                panic!("{} total runs is over the ceiling of {}, game state: {:#?}", total_runs, max_total_runs, game);
+            }
+        }
+    }
+
+    //slots a plugin in by its priority, after any others with the same one
+    pub fn add_plugin(&mut self, plugin: Box<dyn Plugin>) {
+        let idx = self.plugins.partition_point(|p| p.priority() <= plugin.priority());
+        self.plugins.insert(idx, plugin);
+    }
+
+    //a plugin with the default priority goes right before the pitch formulas, so it gets first say on every pitch.
+    //whatever it passes on (returns None for) goes through the stock formulas
+    pub fn set_pitch_model(&mut self, model: Box<dyn Plugin>) {
+        self.add_plugin(model);
+    }
+
+    //matchup_odds for the game in progress, checked to not have touched the rng
+    pub fn matchup_odds(&mut self, game: &Game) -> MatchupOdds {
+        let world = &*self.world;
+        self.rng.frozen(|_| matchup_odds(game, world))
+    }
+
+    //the rolls behind the last event, if it came from a pitch
+    pub fn last_pitch(&self) -> Option<PitchDetail> {
+        self.last_pitch.get()
+    }
+
+    //for big monte carlo runs where only the final score matters: plays the game out
+    //and hands back how it ended. nothing's collected along the way, and game.events
+    //only keeps what the sim itself queries
+    pub fn run_game_stats_only(&mut self, game: &mut Game) -> GameResult {
+        loop {
+            let evt = self.next(game);
+            evt.apply(game, self.world);
+            if let Event::GameOver = evt {
+                break;
+            }
+        }
+        game.result().unwrap()
+    }
+
+    //plays a game to completion and returns how many events it took
+    //bails out after max_events in case the game gets stuck (outs never adding up etc.)
+    //the game is left in the state it got stuck in, game.events has the rest
+    pub fn play(&mut self, game: &mut Game, max_events: usize) -> Result<usize, EventBudgetExceeded> {
+        for events in 1..=max_events {
+            let evt = self.next(game);
+            evt.apply(game, self.world);
+            if let Event::GameOver = evt {
+                return Ok(events);
+            }
+        }
+        Err(EventBudgetExceeded {
+            events: max_events,
+            inning: game.inning,
+            top: game.scoreboard.top,
+            outs: game.outs,
+        })
+    }
+}
+
+//where two pitch models' versions of the same game went apart
+#[derive(Debug, Clone)]
+pub struct ModelDiff {
+    pub first_difference: Option<usize>, //event index, None if the games were identical
+    pub event_a: Option<String>, //the events at that index, None if that game had already ended
+    pub event_b: Option<String>,
+    pub home_score_difference: f64, //b minus a
+    pub away_score_difference: f64,
+}
+
+//plays the same game with the same seed under two pitch models (None being the stock formulas)
+//for checking what a formula tweak actually does. the world passed in isn't touched
+pub fn compare_models(world: &World, matchup: &Matchup, day: usize, seed: (u64, u64), model_a: Option<Box<dyn Plugin>>, model_b: Option<Box<dyn Plugin>>) -> ModelDiff {
+    let play = |model: Option<Box<dyn Plugin>>| {
+        let mut world = world.clone();
+        let mut rng = Rng::new(seed.0, seed.1);
+        let mut game = Game::new(matchup.home_team, matchup.away_team, day, matchup.weather, &world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        if let Some(model) = model {
+            sim.set_pitch_model(model);
+        }
+        //the game's own log doesn't keep everything
+        let mut events = Vec::new();
+        loop {
+            let evt = sim.next(&game);
+            evt.apply(&mut game, sim.world);
+            events.push(evt.to_string());
+            if let Event::GameOver = evt {
+                break;
+            }
+        }
+        (events, game)
+    };
+    let (events_a, game_a) = play(model_a);
+    let (events_b, game_b) = play(model_b);
+
+    let first_difference = (0..events_a.len().max(events_b.len()))
+        .find(|&i| events_a.get(i) != events_b.get(i));
+    ModelDiff {
+        first_difference,
+        event_a: first_difference.and_then(|i| events_a.get(i).cloned()),
+        event_b: first_difference.and_then(|i| events_b.get(i).cloned()),
+        home_score_difference: game_b.scoreboard.home_team.score - game_a.scoreboard.home_team.score,
+        away_score_difference: game_b.scoreboard.away_team.score - game_a.scoreboard.away_team.score,
+    }
+}
+
+//throws n pitches from the same neutral spot (first inning, 0-0, nobody on, no weather)
+//and counts what happens, for seeing what the formulas actually do with a matchup.
+//the two of them get a world of their own with a team each. the pitcher is the whole defense,
+//so their fielding counts for everyone
+pub fn sample_pitch_outcomes(batter: &Player, pitcher: &Player, ruleset: u8, n: usize, rng: &mut Rng) -> HashMap<PitchOutcomeKind, usize> {
+    let mut world = World::new(ruleset);
+    let batting_team = world.new_id(rng);
+    let pitching_team = world.new_id(rng);
+    for (team_id, player) in [(batting_team, batter), (pitching_team, pitcher)] {
+        let mut team = Team::new(team_id, String::new(), String::new());
+        team.lineup.push(player.id);
+        team.rotation.push(player.id);
+        world.insert_team(team);
+        let mut player = player.clone();
+        player.team = Some(team_id);
+        world.insert_player(player);
+    }
+    //away bats first
+    let mut game = Game::new(pitching_team, batting_team, 0, Some(Weather::Sun), &world, rng);
+    game.scoreboard.home_team.pitcher = pitcher.id;
+    game.scoreboard.away_team.batter = Some(batter.id);
+    game.update_multiplier_data(&world);
+
+    let mut tally = HashMap::new();
+    let mut detail = PitchDetail::default();
+    for _ in 0..n {
+        let outcome = do_pitch(&world, &game, rng, &mut detail);
+        *tally.entry(outcome.kind()).or_insert(0) += 1;
+    }
+    tally
+}
+
+//the chances on the next pitch for whoever's up against whoever's pitching,
+//straight from the formulas. for showing next to a game, nothing's rolled
+#[derive(Debug, Clone, Copy)]
+pub struct MatchupOdds {
+    pub strike: f64,
+    pub swing_on_strike: f64,
+    pub swing_on_ball: f64,
+    pub contact_on_strike: f64,
+    pub contact_on_ball: f64,
+    pub foul: f64,
+}
+
+pub fn matchup_odds(game: &Game, world: &World) -> MatchupOdds {
+    let pitcher = world.player(game.pitcher());
+    let batter = world.player(game.batter().unwrap());
+    let ruleset = world.ruleset.season;
+    let multiplier_data = &game.multiplier_data;
+    MatchupOdds {
+        strike: formulas::strike_threshold(pitcher, batter, game.strikes, ruleset, multiplier_data),
+        swing_on_strike: formulas::swing_threshold(pitcher, batter, true, game.strikes, ruleset, multiplier_data),
+        swing_on_ball: formulas::swing_threshold(pitcher, batter, false, game.strikes, ruleset, multiplier_data),
+        contact_on_strike: formulas::contact_threshold(pitcher, batter, true, ruleset, multiplier_data),
+        contact_on_ball: formulas::contact_threshold(pitcher, batter, false, ruleset, multiplier_data),
+        foul: formulas::foul_threshold(pitcher, batter, ruleset, multiplier_data),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EventBudgetExceeded {
+    pub events: usize,
+    pub inning: i16,
+    pub top: bool,
+    pub outs: i16,
 }
 
-enum PitchOutcome {
+//how a pitch went, for showing it pitch by pitch.
+//anything after the pitch ended stays false
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PitchDetail {
+    pub strike: bool,
+    pub swung: bool,
+    pub contact: bool,
+    pub foul: bool,
+    pub out: bool,
+    pub fly: bool,
+}
+
+//what sample_pitch_outcomes tallies, PitchOutcome without the details
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PitchOutcomeKind {
+    Ball,
+    StrikeSwinging,
+    StrikeLooking,
+    Foul,
+    GroundOut,
+    Flyout,
+    DoublePlay,
+    FieldersChoice,
+    HomeRun,
+    Triple,
+    Double,
+    Single,
+    Quadruple,
+}
+
+pub(crate) enum PitchOutcome {
     Ball,
     StrikeSwinging,
     StrikeLooking,
     Foul,
     GroundOut {
         fielder: Uuid,
-        advancing_runners: Vec<Uuid>
+        advancing_runners: RunnerSet
     },
     Flyout { 
         fielder: Uuid,
-        advancing_runners: Vec<Uuid>
+        advancing_runners: RunnerSet
     },
     DoublePlay { runner_out: u8 },
     FieldersChoice { runner_out: u8 },
     HomeRun,
-    Triple { advancing_runners: Vec<Uuid> },
-    Double { advancing_runners: Vec<Uuid> },
-    Single { advancing_runners: Vec<Uuid> },
-    Quadruple { advancing_runners: Vec<Uuid> }
+    Triple { advancing_runners: RunnerSet },
+    Double { advancing_runners: RunnerSet },
+    Single { advancing_runners: RunnerSet },
+    Quadruple { advancing_runners: RunnerSet }
 }
 
-struct BasePlugin;
+impl PitchOutcome {
+    fn kind(&self) -> PitchOutcomeKind {
+        match self {
+            PitchOutcome::Ball => PitchOutcomeKind::Ball,
+            PitchOutcome::StrikeSwinging => PitchOutcomeKind::StrikeSwinging,
+            PitchOutcome::StrikeLooking => PitchOutcomeKind::StrikeLooking,
+            PitchOutcome::Foul => PitchOutcomeKind::Foul,
+            PitchOutcome::GroundOut { .. } => PitchOutcomeKind::GroundOut,
+            PitchOutcome::Flyout { .. } => PitchOutcomeKind::Flyout,
+            PitchOutcome::DoublePlay { .. } => PitchOutcomeKind::DoublePlay,
+            PitchOutcome::FieldersChoice { .. } => PitchOutcomeKind::FieldersChoice,
+            PitchOutcome::HomeRun => PitchOutcomeKind::HomeRun,
+            PitchOutcome::Triple { .. } => PitchOutcomeKind::Triple,
+            PitchOutcome::Double { .. } => PitchOutcomeKind::Double,
+            PitchOutcome::Single { .. } => PitchOutcomeKind::Single,
+            PitchOutcome::Quadruple { .. } => PitchOutcomeKind::Quadruple,
+        }
+    }
+}
+
+struct BasePlugin {
+    last_pitch: Rc<Cell<Option<PitchDetail>>>,
+}
 impl Plugin for BasePlugin {
+    fn priority(&self) -> u32 {
+        100
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
-        let max_balls = game.get_max_balls(world);
-        let max_strikes = game.get_max_strikes(world);
-        // let max_outs = 3;
+        let mut detail = PitchDetail::default();
+        let outcome = do_pitch(world, game, rng, &mut detail);
+        self.last_pitch.set(Some(detail));
+        Some(outcome_event(outcome, game, world, rng))
+    }
+}
 
-        let last_strike = (game.strikes + 1) >= max_strikes;
+//turns how the pitch went into what happens in the game: whether it's the fourth ball or third strike,
+//O No and base instincts, and where the runners end up. instinct walks roll, so this takes the rng
+pub(crate) fn outcome_event(outcome: PitchOutcome, game: &Game, world: &World, rng: &mut Rng) -> Event {
+    let max_balls = game.get_max_balls(world);
+    let max_strikes = game.get_max_strikes(world);
+    // let max_outs = 3;
 
-        Some(match do_pitch(world, game, rng) {
-            PitchOutcome::Ball => {
-                if (game.balls + 1) < max_balls {
-                    Event::Ball
+    let last_strike = (game.strikes + 1) >= max_strikes;
+
+    match outcome {
+        PitchOutcome::Ball => {
+            if (game.balls + 1) < max_balls {
+                Event::Ball
+            } else {
+                if world.player(game.batter().unwrap()).mods.has(Mod::BaseInstincts) && rng.next() < 0.2 {
+                    Event::InstinctWalk { third: rng.next() * rng.next() < 0.5 }
                 } else {
-                    if world.player(game.batter().unwrap()).mods.has(Mod::BaseInstincts) && rng.next() < 0.2 {
-                        Event::InstinctWalk { third: rng.next() * rng.next() < 0.5 }
-                    } else {
-                        Event::Walk
-                    }
+                    Event::Walk
                 }
             }
-            PitchOutcome::StrikeSwinging => {
-                if last_strike {
-                    Event::Strikeout
-                } else {
-                    Event::Strike
-                }
+        }
+        PitchOutcome::StrikeSwinging => {
+            if last_strike {
+                Event::Strikeout
+            } else {
+                Event::Strike
             }
-            PitchOutcome::StrikeLooking => {
-                if last_strike {
-                    if world.team(game.scoreboard.batting_team().id).mods.has(Mod::ONo) && game.balls == 0 {
-                        Event::Foul
-                    } else {
-                        Event::Strikeout
-                    }
+        }
+        PitchOutcome::StrikeLooking => {
+            if last_strike {
+                if world.team(game.scoreboard.batting_team().id).mods.has(Mod::ONo) && game.balls == 0 {
+                    Event::Foul
                 } else {
-                    Event::Strike
+                    Event::Strikeout
                 }
+            } else {
+                Event::Strike
             }
-            PitchOutcome::Foul => Event::Foul,
-            PitchOutcome::GroundOut { fielder, advancing_runners } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
-                Event::GroundOut {
-                    fielder,
-                    runners_after: new_runners,
-                }
-            },
-            PitchOutcome::Flyout { fielder, advancing_runners } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
-                Event::Flyout {
-                    fielder,
-                    runners_after: new_runners,
-                }
-            },
-            PitchOutcome::DoublePlay { runner_out } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.remove(runner_out);
-                new_runners.advance_all(1);
-                Event::DoublePlay {
-                    runners_after: new_runners
-                }
-            },
-            PitchOutcome::FieldersChoice { runner_out } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.remove(runner_out);
-                new_runners.advance_all(1);
-                Event::FieldersChoice {
-                    runners_after: new_runners
-                }
-            },
-
-            PitchOutcome::HomeRun => Event::HomeRun,
-
-            // todo: there may be a subtle bug here since we don't sweep the runners after the forced advance
-            // runner [1, 0], double, then we're at [3, 2], 3 *should* get swept and *then* 2 should get to advance to 3...
-            PitchOutcome::Triple { advancing_runners } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.advance_all(3);
-                new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
-                Event::BaseHit {
-                    bases: 3,
-                    runners_after: new_runners,
-                }
-            },
-
-            PitchOutcome::Double { advancing_runners } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.advance_all(2);
-                new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
-                Event::BaseHit {
-                    bases: 2,
-                    runners_after: new_runners,
-                }
-            },
-
-            PitchOutcome::Single { advancing_runners } => {
-                let mut new_runners = game.runners.clone();
-                new_runners.advance_all(1);
-                new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
-                Event::BaseHit {
-                    bases: 1,
-                    runners_after: new_runners,
-                }
-            },
-
-            PitchOutcome::Quadruple { advancing_runners }=> {
-                let mut new_runners = game.runners.clone();
-                new_runners.advance_all(4);
-                new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
-                Event::BaseHit {
-                    bases: 4,
-                    runners_after: new_runners,
-                }
-            },
-        })
+        }
+        PitchOutcome::Foul => {
+            //no roll unless foul tips are on, so regular games stay lined up
+            let foul_tip = last_strike && world.foul_tips && rng.next() < formulas::foul_tip_threshold(world.ruleset.season);
+            if foul_tip || game.max_fouls.is_some_and(|max_fouls| game.fouls >= max_fouls) {
+                Event::Strikeout
+            } else {
+                Event::Foul
+            }
+        },
+        PitchOutcome::GroundOut { fielder, advancing_runners } => {
+            let mut new_runners = game.runners;
+            new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
+            Event::GroundOut {
+                fielder,
+                runners_after: new_runners,
+            }
+        },
+        PitchOutcome::Flyout { fielder, advancing_runners } => {
+            let mut new_runners = game.runners;
+            new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
+            Event::Flyout {
+                fielder,
+                runners_after: new_runners,
+            }
+        },
+        PitchOutcome::DoublePlay { runner_out } => {
+            let mut new_runners = game.runners;
+            new_runners.remove(runner_out);
+            new_runners.advance_all(1);
+            Event::DoublePlay {
+                runners_after: new_runners
+            }
+        },
+        PitchOutcome::FieldersChoice { runner_out } => {
+            let mut new_runners = game.runners;
+            new_runners.remove(runner_out);
+            new_runners.advance_all(1);
+            Event::FieldersChoice {
+                runners_after: new_runners
+            }
+        },
+
+        PitchOutcome::HomeRun => Event::HomeRun,
+
+        // todo: there may be a subtle bug here since we don't sweep the runners after the forced advance
+        // runner [1, 0], double, then we're at [3, 2], 3 *should* get swept and *then* 2 should get to advance to 3...
+        PitchOutcome::Triple { advancing_runners } => {
+            let mut new_runners = game.runners;
+            new_runners.advance_all(3);
+            new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
+            Event::BaseHit {
+                bases: 3,
+                runners_after: new_runners,
+            }
+        },
+
+        PitchOutcome::Double { advancing_runners } => {
+            let mut new_runners = game.runners;
+            new_runners.advance_all(2);
+            new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
+            Event::BaseHit {
+                bases: 2,
+                runners_after: new_runners,
+            }
+        },
+
+        PitchOutcome::Single { advancing_runners } => {
+            let mut new_runners = game.runners;
+            new_runners.advance_all(1);
+            new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
+            Event::BaseHit {
+                bases: 1,
+                runners_after: new_runners,
+            }
+        },
+
+        PitchOutcome::Quadruple { advancing_runners }=> {
+            let mut new_runners = game.runners;
+            new_runners.advance_all(4);
+            new_runners.advance_if(|runner| advancing_runners.contains(&runner.id));
+            Event::BaseHit {
+                bases: 4,
+                runners_after: new_runners,
+            }
+        },
     }
 }
 
-fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
+pub(crate) fn do_pitch(world: &World, game: &Game, rng: &mut Rng, detail: &mut PitchDetail) -> PitchOutcome {
     let pitcher = world.player(game.pitcher());
     let batter = world.player(game.batter().unwrap());
-    let ruleset = world.season_ruleset; //todo: can we fold this into multiplier_data?
-
-    let is_flinching = game.strikes == 0 && batter.mods.has(Mod::Flinch);
+    let ruleset = world.ruleset.season; //todo: can we fold this into multiplier_data?
 
     let multiplier_data = &game.multiplier_data;
 
-    let is_strike = rng.next() < formulas::strike_threshold(pitcher, batter, is_flinching, ruleset, multiplier_data);
-    let does_swing = if !is_flinching {
-        rng.next() < formulas::swing_threshold(pitcher, batter, is_strike, ruleset, multiplier_data)
-    } else {
+    let is_strike = game.trace_roll("strike", rng.next()) < formulas::strike_threshold(pitcher, batter, game.strikes, ruleset, multiplier_data);
+    //the real game doesn't roll this for a flinching batter, see Ruleset::flinch_swings
+    let does_swing = if formulas::is_flinching(batter, game.strikes) && !world.ruleset.flinch_swings {
         false
+    } else {
+        game.trace_roll("swing", rng.next()) < formulas::swing_threshold(pitcher, batter, is_strike, game.strikes, ruleset, multiplier_data)
     };
+    detail.strike = is_strike;
+    detail.swung = does_swing;
 
     if !does_swing {
         if is_strike {
@@ -211,27 +530,31 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
         }
     }
 
-    let does_contact = rng.next() < formulas::contact_threshold(pitcher, batter, is_strike, ruleset, multiplier_data);
+    let does_contact = game.trace_roll("contact", rng.next()) < formulas::contact_threshold(pitcher, batter, is_strike, ruleset, multiplier_data);
+    detail.contact = does_contact;
     if !does_contact {
         return PitchOutcome::StrikeSwinging;
     }
 
-    let is_foul = rng.next() < formulas::foul_threshold(pitcher, batter, ruleset, multiplier_data);
+    let is_foul = game.trace_roll("foul", rng.next()) < formulas::foul_threshold(pitcher, batter, ruleset, multiplier_data);
+    detail.foul = is_foul;
     if is_foul {
         return PitchOutcome::Foul;
     }
 
-    let out_defender_id = game.pick_fielder(world, rng.next());
+    let out_defender_id = game.pick_fielder(world, game.trace_roll("out_defender", rng.next()));
     let out_defender = world.player(out_defender_id);
 
-    let is_out = rng.next() > formulas::out_threshold(pitcher, batter, out_defender, ruleset, multiplier_data);
+    let is_out = game.trace_roll("out", rng.next()) > formulas::out_threshold(pitcher, batter, out_defender, ruleset, multiplier_data);
+    detail.out = is_out;
     if is_out {
-        let fly_defender_id = game.pick_fielder(world, rng.next());
+        let fly_defender_id = game.pick_fielder(world, game.trace_roll("fly_defender", rng.next()));
         let fly_defender = world.player(fly_defender_id);
 
-        let is_fly = rng.next() < formulas::fly_threshold(batter, pitcher, ruleset, multiplier_data);
+        let is_fly = game.trace_roll("fly", rng.next()) < formulas::fly_threshold(batter, pitcher, ruleset, multiplier_data);
+        detail.fly = is_fly;
         if is_fly {
-            let mut advancing_runners = Vec::new();
+            let mut advancing_runners = RunnerSet::new();
             if game.outs == game.scoreboard.batting_team().max_outs - 1 {
                 return PitchOutcome::Flyout {
                     fielder: fly_defender_id,
@@ -243,7 +566,7 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
                 let runner_id = baserunner.id;
                 let runner = world.player(runner_id);
 
-                if rng.next() < formulas::flyout_advancement_threshold(runner, base_from, ruleset, multiplier_data) {
+                if game.trace_roll("flyout_advancement", rng.next()) < formulas::flyout_advancement_threshold(runner, base_from, ruleset, multiplier_data) {
                     advancing_runners.push(runner_id);
                 }
             }
@@ -253,8 +576,8 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
             };
         }
 
-        let ground_defender_id = game.pick_fielder(world, rng.next());
-        let mut advancing_runners = Vec::new();
+        let ground_defender_id = game.pick_fielder(world, game.trace_roll("ground_defender", rng.next()));
+        let mut advancing_runners = RunnerSet::new();
         if game.outs == game.scoreboard.batting_team().max_outs - 1 {
             return PitchOutcome::GroundOut {
                 fielder: ground_defender_id,
@@ -263,20 +586,23 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
         }
 
         if !game.runners.empty() {
-            let dp_roll = rng.next();
+            let dp_roll = game.trace_roll("double_play", rng.next());
             if game.runners.occupied(0) {
                 //did this actually work in actual blaseball?
                 if game.outs < game.scoreboard.batting_team().max_outs - 1 && dp_roll < formulas::double_play_threshold(batter, pitcher, out_defender, ruleset, multiplier_data) {
+                    //the batter and the lead forced runner are the two outs.
+                    //the runner roll still happens so the rng stays lined up
+                    game.trace_roll("double_play_runner", rng.next());
                     return PitchOutcome::DoublePlay {
-                        runner_out: game.runners.pick_runner(rng.next())
+                        runner_out: game.runners.pick_runner_fc()
                     };
                 } else {
-                    let sac_roll = rng.next();
+                    let sac_roll = game.trace_roll("sacrifice", rng.next());
                     if sac_roll < formulas::groundout_sacrifice_threshold(batter, ruleset, multiplier_data) {
                         for baserunner in game.runners.iter() {
                             let runner_id = baserunner.id.clone();
                             let runner = world.player(runner_id);
-                            if rng.next() < formulas::groundout_advancement_threshold(runner, out_defender, ruleset, multiplier_data) {
+                            if game.trace_roll("groundout_advancement", rng.next()) < formulas::groundout_advancement_threshold(runner, out_defender, ruleset, multiplier_data) {
                                 advancing_runners.push(runner_id);
                             }
                         }
@@ -294,7 +620,7 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
             for baserunner in game.runners.iter() {
                 let runner_id = baserunner.id.clone();
                 let runner = world.player(runner_id);
-                if rng.next() < formulas::groundout_advancement_threshold(runner, out_defender, ruleset, multiplier_data) {
+                if game.trace_roll("groundout_advancement", rng.next()) < formulas::groundout_advancement_threshold(runner, out_defender, ruleset, multiplier_data) {
                     advancing_runners.push(runner_id);
                 }
             }
@@ -305,26 +631,26 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
         };
     }
 
-    let is_hr = rng.next() < formulas::hr_threshold(pitcher, batter, ruleset, multiplier_data);
+    let is_hr = game.trace_roll("home_run", rng.next()) < formulas::hr_threshold(pitcher, batter, ruleset, multiplier_data);
     if is_hr {
         return PitchOutcome::HomeRun;
     }
 
-    let hit_defender_id = game.pick_fielder(world, rng.next());
+    let hit_defender_id = game.pick_fielder(world, game.trace_roll("hit_defender", rng.next()));
     let hit_defender = world.player(hit_defender_id);
-    let double_roll = rng.next();
-    let triple_roll = rng.next();
+    let double_roll = game.trace_roll("double", rng.next());
+    let triple_roll = game.trace_roll("triple", rng.next());
     let mut quadruple_roll = 1.0;
     if game.get_bases(world) == 5 {
-        quadruple_roll = rng.next();
+        quadruple_roll = game.trace_roll("quadruple", rng.next());
     }
 
-    let mut advancing_runners = Vec::new();
+    let mut advancing_runners = RunnerSet::new();
     for baserunner in game.runners.iter() {
         let runner_id = baserunner.id.clone();
         let runner = world.player(runner_id);
 
-        if rng.next() < formulas::hit_advancement_threshold(runner, hit_defender, ruleset, multiplier_data) {
+        if game.trace_roll("hit_advancement", rng.next()) < formulas::hit_advancement_threshold(runner, hit_defender, ruleset, multiplier_data) {
             advancing_runners.push(runner_id);
         }
     }
@@ -353,6 +679,10 @@ fn do_pitch(world: &World, game: &Game, rng: &mut Rng) -> PitchOutcome {
 
 struct BatterStatePlugin;
 impl Plugin for BatterStatePlugin {
+    fn priority(&self) -> u32 {
+        30
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         let batting_team = game.scoreboard.batting_team();
         if game.batter().is_none() {
@@ -366,9 +696,14 @@ impl Plugin for BatterStatePlugin {
                 false
             };
             let inning_begin = !first_batter && game.events.last() == "InningSwitch";
+            //idx 0 means nobody on this team has batted yet, even if the game's going
+            let first_batter = first_batter || idx == 0;
             let prev = if first_batter { team.lineup[0].clone() } else { team.lineup[(idx - 1) % team.lineup.len()].clone() };
             //todo: improve this
-            if !first_batter && !inning_begin && world.player(prev).mods.has(Mod::Reverberating) && rng.next() < 0.2 { //rough estimate
+            //the roll happens even past the limit so the rng stays in line
+            if !first_batter && !inning_begin && world.player(prev).mods.has(Mod::Reverberating)
+                && rng.next() < formulas::reverberating_threshold()
+                && game.reverberations_inning < REVERBERATING_LIMIT {
                 return Some(Event::Reverberating { batter: prev });
             } else if !first_batter && !inning_begin && world.player(prev).mods.has(Mod::Repeating) && (game.events.last() == "BaseHit" || game.events.last() == "HomeRun") {
                 if let Weather::Reverb = game.weather {
@@ -381,8 +716,10 @@ impl Plugin for BatterStatePlugin {
             } else if world.player(batter).mods.has(Mod::Elsewhere) {
                 return Some(Event::Elsewhere { batter });
             } else if world.player(batter).mods.has(Mod::Haunted) && rng.next() < 0.2 {
-                let inhabit = world.random_hall_player(rng);
-                return Some(Event::Inhabiting { batter, inhabit });
+                //nobody to inhabit with an empty hall, they just bat
+                if let Some(inhabit) = world.random_hall_player(rng) {
+                    return Some(Event::Inhabiting { batter, inhabit });
+                }
             }
             Some(Event::BatterUp { batter })
         } else {
@@ -393,31 +730,46 @@ impl Plugin for BatterStatePlugin {
 
 struct InningStatePlugin;
 impl Plugin for InningStatePlugin {
-    fn tick(&self, game: &Game, _world: &World, _rng: &mut Rng) -> Option<Event> {
+    fn priority(&self) -> u32 {
+        10
+    }
+
+    fn tick(&self, game: &Game, world: &World, _rng: &mut Rng) -> Option<Event> {
+        //with World::walk_offs the winning run ends it right there, whoever's up or on base
+        if world.walk_offs
+            && !game.scoreboard.top
+            && game.inning >= game.get_max_innings(world)
+            && game.lead() == Ordering::Greater {
+            return Some(Event::GameOver);
+        }
+
         if game.outs < game.scoreboard.batting_team().max_outs {
             return None;
         }
 
-        let lead = if (game.scoreboard.away_team.score - game.scoreboard.home_team.score).abs() < 0.01 {
-            0
-        } else if game.scoreboard.away_team.score > game.scoreboard.home_team.score {
-            1
-        } else {
-            -1
-        }; // lol floats
-        if game.inning >= 9 && (lead == -1 || !game.scoreboard.top && lead == 1) {
-            return Some(Event::GameOver);
+        //the home team leading after any half of the last inning (or later) wins it,
+        //the away team has to survive the bottom half too.
+        //a tie just keeps going into extras.
+        //no walk-offs in blaseball (unless World::walk_offs), this only runs once the half is over
+        if game.inning >= game.get_max_innings(world) {
+            match game.lead() {
+                Ordering::Greater => return Some(Event::GameOver),
+                Ordering::Less if !game.scoreboard.top => return Some(Event::GameOver),
+                _ => {}
+            }
         }
 
         if game.scoreboard.top {
             Some(Event::InningSwitch {
                 inning: game.inning,
                 top: false,
+                runs_this_half: game.scoreboard.away_team.score - game.linescore_away[0],
             })
         } else {
             Some(Event::InningSwitch {
                 inning: game.inning + 1,
                 top: true,
+                runs_this_half: game.scoreboard.home_team.score - game.linescore_home[0],
             })
         }
     }
@@ -425,6 +777,10 @@ impl Plugin for InningStatePlugin {
 
 struct StealingPlugin;
 impl Plugin for StealingPlugin {
+    fn priority(&self) -> u32 {
+        STEALING_PRIORITY
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         let steal_defender_id = game.pick_fielder(world, rng.next());
         let steal_defender = world.player(steal_defender_id);
@@ -434,12 +790,7 @@ impl Plugin for StealingPlugin {
             if let Some(runner_id) = game.runners.at(base) {
                 if game.runners.can_advance(base) {
                     let runner = world.player(runner_id);
-                    let should_attempt =
-                        rng.next() < formulas::steal_attempt_threshold(runner, steal_defender);
-                    if should_attempt {
-                        let success =
-                            rng.next() < formulas::steal_success_threshold(runner, steal_defender);
-
+                    if let Some(success) = roll_steal(runner, steal_defender, rng) {
                         if success {
                             return Some(Event::BaseSteal {
                                 runner: runner_id,
@@ -461,13 +812,47 @@ impl Plugin for StealingPlugin {
     }
 }
 
+//None if the runner stays put, otherwise whether the steal succeeded
+fn roll_steal(runner: &Player, defender: &Player, rng: &mut Rng) -> Option<bool> {
+    let should_attempt = rng.next() < formulas::steal_attempt_threshold(runner, defender);
+    if should_attempt {
+        Some(rng.next() < formulas::steal_success_threshold(runner, defender))
+    } else {
+        None
+    }
+}
+
+//for tuning steal rates without the rest of the sim getting in the way
+//returns (attempts, successes)
+pub fn simulate_steals(runner: &Player, defender: &Player, n: usize, rng: &mut Rng) -> (usize, usize) {
+    let mut attempts = 0;
+    let mut successes = 0;
+    for _ in 0..n {
+        if let Some(success) = roll_steal(runner, defender, rng) {
+            attempts += 1;
+            if success {
+                successes += 1;
+            }
+        }
+    }
+    (attempts, successes)
+}
+
 //exclusion: "all", "current", "playing"
-fn poll_for_mod(game: &Game, world: &World, a_mod: Mod, exclusion: &str, team: bool) -> Vec<Uuid> {
+//the answer only changes when an event does something, so it's cached on the game (see Game::poll_cache)
+fn poll_for_mod(game: &Game, world: &World, a_mod: Mod, exclusion: &'static str, team: bool) -> Vec<Uuid> {
+    game.poll_cache
+        .borrow_mut()
+        .entry((a_mod, exclusion, team))
+        .or_insert_with(|| poll_for_mod_uncached(game, world, a_mod, exclusion, team))
+        .clone()
+}
+
+fn poll_for_mod_uncached(game: &Game, world: &World, a_mod: Mod, exclusion: &str, team: bool) -> Vec<Uuid> {
     let home_team = &game.scoreboard.home_team;
     let away_team = &game.scoreboard.away_team;
 
-    //not good that runners.runners is accessed directly
-    let home_lineup = if !game.scoreboard.top && exclusion == "playing" { [vec![game.batter().unwrap()], game.runners.iter().map(|r| r.id).collect()].concat() } else { world.team(home_team.id).lineup.clone() };
+    let home_lineup = if !game.scoreboard.top && exclusion == "playing" { [vec![game.batter().unwrap()], game.runners.occupants()].concat() } else { world.team(home_team.id).lineup.clone() };
     let home_pitcher = if exclusion != "all" { 
         if !game.scoreboard.top && exclusion == "playing" {
             Vec::new()
@@ -477,7 +862,7 @@ fn poll_for_mod(game: &Game, world: &World, a_mod: Mod, exclusion: &str, team: b
     } else { 
         world.team(home_team.id).rotation.clone() 
     };
-    let away_lineup = if game.scoreboard.top && exclusion == "playing" { [vec![game.batter().unwrap()], game.runners.iter().map(|r| r.id).collect()].concat() } else { world.team(away_team.id).lineup.clone() };
+    let away_lineup = if game.scoreboard.top && exclusion == "playing" { [vec![game.batter().unwrap()], game.runners.occupants()].concat() } else { world.team(away_team.id).lineup.clone() };
     let away_pitcher = if exclusion != "all" { 
         if game.scoreboard.top && exclusion == "playing" {
             Vec::new()
@@ -502,16 +887,26 @@ fn poll_for_mod(game: &Game, world: &World, a_mod: Mod, exclusion: &str, team: b
 
 struct WeatherPlugin;
 impl Plugin for WeatherPlugin {
+    fn priority(&self) -> u32 {
+        40
+    }
+
+    //the weathers that never roll here (flooding and salmon have their own plugins)
+    fn applies(&self, game: &Game, _world: &World) -> bool {
+        !matches!(game.weather, Weather::Sun | Weather::Coffee3 | Weather::Flooding | Weather::Salmon | Weather::SunPointOne | Weather::SumSun)
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         let fort = 0.0;
-        let ruleset = world.season_ruleset;
         match game.weather {
             Weather::Sun => None,
             Weather::Eclipse => {
                 //todo: add fortification
-                let fire_eaters = poll_for_mod(game, world, Mod::FireEater, "playing", false);
+                let mut fire_eaters = poll_for_mod(game, world, Mod::FireEater, "playing", false);
                 let incin_roll = rng.next();
-                //todo: the Fire Eater picker prioritizes unstable players
+                //the Fire Eater picker prioritizes unstable players
+                //the sort is stable so the poll order holds otherwise
+                fire_eaters.sort_by_key(|&fe| !world.player(fe).mods.has(Mod::Unstable));
                 if fire_eaters.len() > 0 {
                     for fe in fire_eaters {
                         if rng.next() < 0.002 { //estimate
@@ -519,11 +914,12 @@ impl Plugin for WeatherPlugin {
                         }
                     }
                 }
-                let target = game.pick_player_weighted(world, rng.next(), |&uuid| !game.runners.contains(uuid), true);
+                //nobody eligible means nobody to incinerate
+                let target = game.pick_player_weighted(world, rng.next(), |&uuid| !game.runners.contains(uuid), true)?;
                 let unstable_check = world.player(target).mods.has(Mod::Unstable) && incin_roll < 0.002; //estimate
                 let regular_check = incin_roll < 0.00045 - 0.0004 * fort;
                 if unstable_check || regular_check {
-                    if world.player(target).mods.has(Mod::Fireproof) || world.team(world.player(target).team.unwrap()).mods.has(Mod::Fireproof) {
+                    if world.player_or_team_has(target, world.player(target).team.unwrap(), Mod::Fireproof) {
                         return Some(Event::Fireproof { target });
                     }
                     let minimized = poll_for_mod(game, world, Mod::Minimized, "all", false);
@@ -544,16 +940,25 @@ impl Plugin for WeatherPlugin {
                     let chain: Option<Uuid> = None;
                     if unstable_check {
                         let chain_target = game.pick_player_weighted(world, rng.next(), |&uuid| world.player(uuid).team.unwrap() != world.player(target).team.unwrap(), false);
-                        let chain = if world.player(chain_target).mods.has(Mod::Stable) { None } else { Some(chain_target) };//assumption
+                        let chain = chain_target.filter(|&ct| !world.player(ct).mods.has(Mod::Stable));//assumption
                     }
-                    let replacement = if world.player(target).mods.has(Mod::Squiddish) {
-                        world.player(world.random_hall_player(rng)).clone()
+                    let hall_replacement = if world.player(target).mods.has(Mod::Squiddish) {
+                        world.random_hall_player(rng)
                     } else {
-                        Player::new(rng)
+                        None
+                    };
+                    //squiddish with an empty hall gets a regular replacement,
+                    //which comes out of the replacement pool if there's anyone in it
+                    let replacement = if let Some(hall_player) = hall_replacement {
+                        world.player(hall_player).clone()
+                    } else if let Some(prospect) = world.replacement_pool.first() {
+                        prospect.clone()
+                    } else {
+                        Player::new_with_id(world.new_id(rng), rng)
                     };
                     let ambush = (
-                        if ambush_active.0 { Some(world.random_hall_player(rng)) } else { None },
-                        if ambush_active.1 { Some(world.random_hall_player(rng)) } else { None }
+                        if ambush_active.0 { world.random_hall_player(rng) } else { None },
+                        if ambush_active.1 { world.random_hall_player(rng) } else { None }
                     );
                     Some(Event::Incineration { 
                         target,
@@ -569,14 +974,14 @@ impl Plugin for WeatherPlugin {
                 if rng.next() < 0.000002 { //estimate
                     //this is maybe not rng compliant
                     let target = game.pick_player_weighted(world, rng.next(), |&_uuid| true, true); //theory
-                    Some(Event::BigPeanut {
+                    target.map(|target| Event::BigPeanut {
                         target
                     })
                 } else if rng.next() < 0.0006 - 0.00055 * fort {
                     //idk if runners can have a reaction
                     //but this is assuming it's the same as incins
                     let target = game.pick_player_weighted(world, rng.next(), |&uuid| !game.runners.contains(uuid), true);
-                    Some(Event::Peanut {
+                    target.map(|target| Event::Peanut {
                         target,
                         yummy: false
                     })
@@ -636,7 +1041,7 @@ impl Plugin for WeatherPlugin {
 
                     if feedback_check {   
                         let batting_team = world.team(game.scoreboard.batting_team().id);
-                        let idx = (rng.next() * (batting_team.rotation.len() as f64)).floor() as usize;
+                        let idx = rng.index(batting_team.rotation.len());
                         let target2_raw = batting_team.rotation[idx];
                         target1_opt = Some(pitcher);
                         target2_opt = Some(target2_raw);
@@ -645,7 +1050,14 @@ impl Plugin for WeatherPlugin {
                 if target1_opt.is_some() {
                     let target1 = target1_opt.unwrap();
                     let target2 = target2_opt.unwrap();
-                    if world.player(target1).mods.has(Mod::Soundproof) {
+                    //soundproof only tangles the other target, so if they're both soundproof
+                    //there's nobody to tangle and nothing gets rolled
+                    if world.player(target1).mods.has(Mod::Soundproof) && world.player(target2).mods.has(Mod::Soundproof) {
+                        Some(Event::BothSoundproof {
+                            target1,
+                            target2
+                        })
+                    } else if world.player(target1).mods.has(Mod::Soundproof) {
                         let decreases = roll_random_boosts(rng, 0.0, -0.05, true);
                         Some(Event::Soundproof {
                             resists: target1,
@@ -674,13 +1086,13 @@ impl Plugin for WeatherPlugin {
                 if rng.next() < 0.00003 {
                     let reverb_type_roll = rng.next();
                     let reverb_type = if reverb_type_roll < 0.09 {
-                        0u8
+                        ReverbType::Full
                     } else if reverb_type_roll < 0.55 {
-                        1u8
+                        ReverbType::Several
                     } else if reverb_type_roll < 0.95 {
-                        2u8
+                        ReverbType::Lineup
                     } else {
-                        3u8
+                        ReverbType::Rotation
                     };
                     let team_id = if rng.next() < 0.5 {
                         game.scoreboard.home_team.id
@@ -715,11 +1127,7 @@ impl Plugin for WeatherPlugin {
                 }
             },
             Weather::Blooddrain => {
-                let drain_threshold = if ruleset < 16 { 
-                    0.00065 - 0.001 * fort 
-                } else {
-                    0.00125 - 0.00125 * fort
-                };
+                let drain_threshold = world.ruleset.blooddrain.at(fort);
                 let siphon_threshold = 0.0025;
                 let siphons = poll_for_mod(game, world, Mod::Siphon, "playing", false);
                 let drain_roll = rng.next();
@@ -741,7 +1149,7 @@ impl Plugin for WeatherPlugin {
                                 let hitter = if game.runners.empty() {
                                     game.batter().unwrap()
                                 } else {
-                                    game.pick_player_weighted(world, rng.next(), |&uuid| uuid == game.batter().unwrap() || game.runners.contains(uuid), true)
+                                    game.pick_player_weighted(world, rng.next(), |&uuid| uuid == game.batter().unwrap() || game.runners.contains(uuid), true)?
                                 };
                                 target = hitter
                             }
@@ -759,7 +1167,7 @@ impl Plugin for WeatherPlugin {
                             let hitter = if game.runners.empty() {
                                 game.batter().unwrap()
                             } else {
-                                game.pick_player_weighted(world, rng.next(), |&uuid| uuid == game.batter().unwrap() || game.runners.contains(uuid), true)
+                                game.pick_player_weighted(world, rng.next(), |&uuid| uuid == game.batter().unwrap() || game.runners.contains(uuid), true)?
                             };
                             drainer = if fielding_team_drains { fielder } else { hitter };
                             target = if fielding_team_drains { hitter } else { fielder };
@@ -789,7 +1197,7 @@ impl Plugin for WeatherPlugin {
                         Some(Event::Blooddrain {
                             drainer,
                             target,
-                            stat: (rng.next() * 4.0).floor() as u8,
+                            stat: [StatCategory::Pitching, StatCategory::Batting, StatCategory::Defense, StatCategory::Baserunning][rng.index(4)],
                             siphon,
                             siphon_effect
                         })
@@ -813,7 +1221,7 @@ impl Plugin for WeatherPlugin {
                         //this finds the max of a vec without cloning. I guess.
                         Some(world.team(game.scoreboard.away_team.id).lineup
                             .iter()
-                            .reduce(|acc, e| if world.player(*acc).player_rating(0) > world.player(*e).player_rating(0) { acc } else { e })
+                            .reduce(|acc, e| if world.player(*acc).batting() > world.player(*e).batting() { acc } else { e })
                             .unwrap())
                             .copied()
                     } else {
@@ -824,7 +1232,7 @@ impl Plugin for WeatherPlugin {
                     let carcinized = if world.team(game.scoreboard.away_team.id).mods.has(Mod::Carcinization) {
                         Some(world.team(game.scoreboard.home_team.id).lineup
                             .iter()
-                            .reduce(|acc, e| if world.player(*acc).player_rating(0) > world.player(*e).player_rating(0) { acc } else { e })
+                            .reduce(|acc, e| if world.player(*acc).batting() > world.player(*e).batting() { acc } else { e })
                             .unwrap())
                             .copied()
                     } else {
@@ -864,7 +1272,7 @@ impl Plugin for WeatherPlugin {
                 if rng.next() < 0.01 { //estimate
                     let batter = rng.next() < 0.5;
                     let shadows = if batter { &world.team(game.scoreboard.batting_team().id).shadows } else { &world.team(game.scoreboard.pitching_team().id).shadows };
-                    let replacement_idx = (rng.next() * shadows.len() as f64).floor() as usize;
+                    let replacement_idx = rng.index(shadows.len());
                     let replacement = shadows[replacement_idx as usize];
                     let boosts = roll_random_boosts(rng, 0.0, 0.2, false);
                     Some(Event::NightShift { batter, replacement, replacement_idx, boosts })
@@ -888,6 +1296,10 @@ pub fn roll_random_boosts(rng: &mut Rng, base: f64, threshold: f64, exclude_pres
 
 struct InningEventPlugin;
 impl Plugin for InningEventPlugin {
+    fn priority(&self) -> u32 {
+        20
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         let activated = |event: &str| game.events.has(String::from(event), -1);
         //note: inning events happen after the inning switch
@@ -961,7 +1373,7 @@ impl Plugin for InningEventPlugin {
         if let Weather::Salmon = game.weather {
             let away_team_scored = game.linescore_away.last().unwrap().abs() > 0.01;
             let home_team_scored = if !game.scoreboard.top { false } else { game.linescore_home.last().unwrap().abs() > 0.01 };
-            if game.events.len() > 0 && game.events.last() == "InningSwitch" && (away_team_scored || home_team_scored) {
+            if game.events.total() > 0 && game.events.last() == "InningSwitch" && (away_team_scored || home_team_scored) {
                 let salmon_activated = rng.next() < 0.1375;
                 if salmon_activated {
                     let runs_lost = rng.next() < 0.675; //rough estimate
@@ -990,17 +1402,22 @@ impl Plugin for InningEventPlugin {
 
 struct ModPlugin;
 impl Plugin for ModPlugin {
+    fn priority(&self) -> u32 {
+        80
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         //this whole function? rulesets
         let batter = game.batter().unwrap();
         let batter_mods = &world.player(batter).mods;
-        let batter_team_mods = &world.team(game.scoreboard.batting_team().id).mods;
+        let batting_team = game.scoreboard.batting_team().id;
         let pitcher = game.pitcher();
         let pitcher_mods = &world.player(pitcher).mods;
-        let pitcher_team_mods = &world.team(game.scoreboard.pitching_team().id).mods;
-        if batter_team_mods.has(Mod::Electric) && game.strikes > 0 && rng.next() < 0.2 {
+        let pitching_team = game.scoreboard.pitching_team().id;
+        let electric_threshold = formulas::electric_threshold(world.ruleset.season);
+        if world.player_or_team_has(batter, batting_team, Mod::Electric) && game.strikes > 0 && rng.next() < electric_threshold {
             return Some(Event::Zap { batter: true });
-        } else if pitcher_team_mods.has(Mod::Electric) && game.balls > 0 && rng.next() < 0.2 {
+        } else if world.player_or_team_has(pitcher, pitching_team, Mod::Electric) && game.balls > 0 && rng.next() < electric_threshold {
             return Some(Event::Zap { batter: false });
         } else if pitcher_mods.has(Mod::DebtU) && !batter_mods.has(Mod::Unstable) && rng.next() < 0.02 { //estimate
             return Some(Event::HitByPitch { target: batter, hbp_type: 0 });
@@ -1009,6 +1426,7 @@ impl Plugin for ModPlugin {
         } else if pitcher_mods.has(Mod::ConsolidatedDebt) && !batter_mods.has(Mod::Repeating) && rng.next() < 0.02 { //estimate
             return Some(Event::HitByPitch { target: batter, hbp_type: 2 });
         } else if pitcher_mods.has(Mod::FriendOfCrows) {
+            //an ambush ends the pa, so it can't go off twice on the same batter
             if let Weather::Birds = game.weather {
                 if rng.next() < 0.0255 {
                     return Some(Event::CrowAmbush);
@@ -1016,18 +1434,14 @@ impl Plugin for ModPlugin {
             }
         }
         if rng.next() < 0.005 && pitcher_mods.has(Mod::Mild) {
-            if game.balls == 3 {
+            if game.balls + 1 >= game.get_max_balls(world) {
                 return Some(Event::MildWalk);
             } else {
                 return Some(Event::MildPitch);
             }
-        } else if game.balls == 0 && game.strikes == 0 {
+        } else if game.is_first_pitch() {
             let myst = 0.0;
-        let charm_threshold = if world.season_ruleset == 18 {
-                0.014 + 0.006 * myst
-            } else {
-                0.015 + 0.02 * myst
-            };
+            let charm_threshold = world.ruleset.charm.at(myst);
             if batter_mods.has(Mod::Charm) && rng.next() < charm_threshold {
                 return Some(Event::CharmWalk);
             } else if pitcher_mods.has(Mod::Charm) && rng.next() < charm_threshold {
@@ -1045,6 +1459,10 @@ impl Plugin for ModPlugin {
 
 struct PregamePlugin;
 impl Plugin for PregamePlugin {
+    fn priority(&self) -> u32 {
+        0
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         let activated = |event: &str| game.events.has(String::from(event), -1);
         if !game.started {
@@ -1053,15 +1471,16 @@ impl Plugin for PregamePlugin {
                     return Some(Event::TripleThreat);
                 }
             }
-            let mut overperforming = vec![];
-            let mut underperforming = vec![];
-            //todo: make this a separate event
+            //each performing player comes with the mod that did it
+            let mut overperforming: Vec<(Uuid, Mod)> = vec![];
+            let mut underperforming: Vec<(Uuid, Mod)> = vec![];
+            let with_reason = |players: Vec<Uuid>, reason: Mod| players.into_iter().map(move |p| (p, reason));
             let superyummy = poll_for_mod(game, world, Mod::Superyummy, "current", false);
             if superyummy.len() > 0 {
                 if let Weather::Peanuts = game.weather {
-                    overperforming = [overperforming, superyummy].concat();
+                    overperforming.extend(with_reason(superyummy, Mod::Superyummy));
                 } else {
-                    underperforming = [underperforming, superyummy].concat();
+                    underperforming.extend(with_reason(superyummy, Mod::Superyummy));
                 }
             }
             
@@ -1069,7 +1488,7 @@ impl Plugin for PregamePlugin {
             if perk.len() > 0 {
                 match game.weather {
                     Weather::Coffee | Weather::Coffee2 | Weather::Coffee3 => {
-                        overperforming = [overperforming, perk].concat();
+                        overperforming.extend(with_reason(perk, Mod::Perk));
                     },
                     _ => {}
                 }
@@ -1078,14 +1497,14 @@ impl Plugin for PregamePlugin {
             if game.day < 27 {
                 let earlbirds = poll_for_mod(game, world, Mod::Earlbirds, "current", true);
                 if earlbirds.len() > 0 {
-                    overperforming = [overperforming, earlbirds].concat();
+                    overperforming.extend(with_reason(earlbirds, Mod::Earlbirds));
                 }
             }
 
             if game.day > 71 && game.day < 99 {
                 let lateparty = poll_for_mod(game, world, Mod::LateToTheParty, "current", true);
                 if lateparty.len() > 0 {
-                    overperforming = [overperforming, lateparty].concat();
+                    overperforming.extend(with_reason(lateparty, Mod::LateToTheParty));
                 }
             }
                 
@@ -1103,9 +1522,13 @@ impl Plugin for PregamePlugin {
 
 struct PartyPlugin;
 impl Plugin for PartyPlugin {
+    fn priority(&self) -> u32 {
+        60
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         let party_roll = rng.next();
-        let party_threshold = if world.season_ruleset < 20 { 0.0055 } else { 0.00525 };
+        let party_threshold = world.ruleset.party;
         if party_roll < party_threshold {
             let party_team = if rng.next() < 0.5 { world.team(game.scoreboard.home_team.id) } else { world.team(game.scoreboard.away_team.id) };
             if party_team.partying {
@@ -1117,7 +1540,7 @@ impl Plugin for PartyPlugin {
                 } else {
                     party_team.rotation[index - lineup_length]
                 };
-                let party_number = if world.player(target).mods.has(Mod::LifeOfTheParty) { 0.048 } else { 0.04 };
+                let party_number = formulas::party_boost(world.player(target).mods.has(Mod::LifeOfTheParty), world.ruleset.season);
                 let boosts = roll_random_boosts(rng, party_number, party_number, true);
                 Some(Event::Party { target, boosts })
             } else {
@@ -1131,16 +1554,18 @@ impl Plugin for PartyPlugin {
 
 struct FloodingPlugin;
 impl Plugin for FloodingPlugin {
+    fn priority(&self) -> u32 {
+        70
+    }
+
+    fn applies(&self, game: &Game, _world: &World) -> bool {
+        matches!(game.weather, Weather::Flooding)
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
         if let Weather::Flooding = game.weather {
             let fort = 0.0;
-            let flooding_threshold = match world.season_ruleset {
-                11..14 => 0.019 - 0.02 * fort,
-                14..17 => 0.013 - 0.012 * fort,
-                17 => 0.015 - 0.012 * fort,
-                18..24 => 0.016 - 0.012 * fort,
-                _ => 0.0,
-            };
+            let flooding_threshold = world.ruleset.flooding.at(fort);
             if rng.next() < flooding_threshold {
                 let mut elsewhere: Vec<Uuid> = Vec::new();
                 for runner in game.runners.iter() {
@@ -1161,14 +1586,12 @@ impl Plugin for FloodingPlugin {
 
 struct ElsewherePlugin;
 impl Plugin for ElsewherePlugin {
+    fn priority(&self) -> u32 {
+        50
+    }
+
     fn tick(&self, game: &Game, world: &World, rng: &mut Rng) -> Option<Event> {
-        let elsewhere_return_threshold = match world.season_ruleset {
-            11 => 0.001,
-            12 => 0.000575,
-            13..18 => 0.0004,
-            18..24 => 0.00035,
-            _ => 0.0
-        };
+        let elsewhere_return_threshold = world.ruleset.elsewhere_return;
         let lineup = &world.team(game.scoreboard.batting_team().id).lineup;
         let rotation = &world.team(game.scoreboard.batting_team().id).rotation;
         let mut returned = Vec::new(); //ugh
@@ -1215,15 +1638,7 @@ impl Plugin for ElsewherePlugin {
         if returned.len() > 0 && game.events.last() != "ElsewhereReturn" {
             Some(Event::ElsewhereReturn { returned, letters })
         } else {
-            let unscatter_threshold = match world.season_ruleset {
-                11 | 12 => 0.00061,
-                13 => 0.0005,
-                14..17 => 0.0004,
-                17..20 => 0.00042,
-                20 | 21 => 0.000485,
-                22 | 23 => 0.000495,
-                _ => 0.0
-            };
+            let unscatter_threshold = world.ruleset.unscatter;
             let mut unscattered = Vec::new();
             for &player in lineup {
                 if world.player(player).mods.has(Mod::Scattered) && rng.next() < unscatter_threshold {
@@ -1243,3 +1658,1124 @@ impl Plugin for ElsewherePlugin {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::{test_world, GameScenario};
+    use crate::mods::ModLifetime;
+
+    //Sim::next without the applies check
+    fn next_naive(sim: &mut Sim, game: &Game) -> Event {
+        for plugin in sim.plugins.iter() {
+            if let Some(event) = plugin.tick(game, sim.world, sim.rng) {
+                return event;
+            }
+        }
+        panic!("uhhh")
+    }
+
+    fn play_to_strings(weather: Weather, naive: bool) -> Vec<String> {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(weather).build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        let mut events = Vec::new();
+        loop {
+            let evt = if naive { next_naive(&mut sim, &game) } else { sim.next(&game) };
+            evt.apply(&mut game, sim.world);
+            events.push(evt.to_string());
+            if let Event::GameOver = evt {
+                break;
+            }
+        }
+        events
+    }
+
+    //skipping plugins that don't apply can't change anything, not even the rolls
+    #[test]
+    fn skipping_plugins_matches_naive() {
+        for weather in [Weather::Sun, Weather::Flooding, Weather::Birds] {
+            assert_eq!(play_to_strings(weather, false), play_to_strings(weather, true), "{}", weather);
+        }
+    }
+
+    #[test]
+    fn matchup_odds_doesnt_roll() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        //get someone up to bat
+        while game.batter().is_none() {
+            let evt = sim.next(&game);
+            evt.apply(&mut game, sim.world);
+        }
+        let draws = sim.rng.draws();
+        let odds = sim.matchup_odds(&game);
+        assert_eq!(sim.rng.draws(), draws);
+        assert!(odds.strike > 0.0 && odds.strike < 1.0);
+    }
+
+    //runners on first and second: the one on second is the lead force and goes with the batter
+    #[test]
+    fn double_play_retires_the_lead_forced_runner() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .runner(1, lineup[1])
+            .runner(0, lineup[2])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+
+        let mut detail = PitchDetail::default();
+        let outcome = (0..100000)
+            .map(|_| do_pitch(&world, &game, &mut rng, &mut detail))
+            .find(|o| matches!(o, PitchOutcome::DoublePlay { .. }))
+            .expect("no double play");
+        let event = outcome_event(outcome, &game, &world, &mut rng);
+        event.apply(&mut game, &mut world);
+
+        assert_eq!(game.outs, 2);
+        assert!(!game.runners.contains(lineup[1]));
+        assert_eq!(game.runners.at(1), Some(lineup[2]));
+        assert_eq!(game.runners.len(), 1);
+    }
+
+    //same for a fielder's choice, only the batter's safe at first
+    #[test]
+    fn fielders_choice_retires_the_lead_forced_runner() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .runner(1, lineup[1])
+            .runner(0, lineup[2])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+
+        let mut detail = PitchDetail::default();
+        let outcome = (0..100000)
+            .map(|_| do_pitch(&world, &game, &mut rng, &mut detail))
+            .find(|o| matches!(o, PitchOutcome::FieldersChoice { .. }))
+            .expect("no fielder's choice");
+        assert!(matches!(outcome, PitchOutcome::FieldersChoice { runner_out: 1 }));
+        let event = outcome_event(outcome, &game, &world, &mut rng);
+        event.apply(&mut game, &mut world);
+
+        assert_eq!(game.outs, 1);
+        assert!(!game.runners.contains(lineup[1]));
+        assert_eq!(game.runners.at(1), Some(lineup[2]));
+        assert_eq!(game.runners.at(0), Some(lineup[0]));
+        assert_eq!(game.runners.len(), 2);
+    }
+
+    //what InningStatePlugin says about the half once it's over
+    fn runs_this_half(game: &mut Game, world: &World) -> f64 {
+        game.outs = 3;
+        let mut rng = Rng::new(69, 420);
+        let event = InningStatePlugin.tick(game, world, &mut rng).unwrap();
+        match event {
+            Event::InningSwitch { runs_this_half, .. } => runs_this_half,
+            _ => panic!("expected an inning switch, got {}", event),
+        }
+    }
+
+    #[test]
+    fn inning_switch_reports_runs_this_half() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.score += 2.0;
+        assert_eq!(runs_this_half(&mut game, &world), 2.0);
+    }
+
+    //sun 2 and the black hole take 10 off the score, but those runs still went in this half
+    #[test]
+    fn inning_switch_counts_runs_lost_to_overflow() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun2).build(&mut world, &mut rng);
+        game.scoreboard.away_team.score += 11.0;
+        Event::Sun2 { home_team: false }.apply(&mut game, &mut world);
+        assert_eq!(runs_this_half(&mut game, &world), 11.0);
+        let event = InningStatePlugin.tick(&game, &world, &mut rng).unwrap();
+        event.apply(&mut game, &mut world);
+        assert_eq!(game.linescore_away[0], game.scoreboard.away_team.score);
+
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::BlackHole).build(&mut world, &mut rng);
+        game.scoreboard.top = false;
+        let before = game.scoreboard.home_team.score;
+        game.scoreboard.home_team.score += 10.0;
+        Event::BlackHole { home_team: true, carcinized: None }.apply(&mut game, &mut world);
+        assert_eq!(runs_this_half(&mut game, &world), 10.0);
+        assert_eq!(game.scoreboard.home_team.score, before);
+    }
+
+    #[test]
+    fn flinch_only_holds_off_on_strike_zero() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .player_mod(batter, Mod::Flinch, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(batter);
+
+        let mut swing_rate = |game: &Game| {
+            let mut swings = 0;
+            for _ in 0..10000 {
+                let mut detail = PitchDetail::default();
+                do_pitch(&world, game, &mut rng, &mut detail);
+                if detail.swung {
+                    swings += 1;
+                }
+            }
+            swings as f64 / 10000.0
+        };
+        assert!(swing_rate(&game) < 0.01);
+        game.strikes = 1;
+        assert!(swing_rate(&game) > 0.2);
+    }
+
+    //the forced take doesn't roll to swing unless the ruleset says so, so the rng stays lined up with the real game
+    #[test]
+    fn flinch_take_skips_the_swing_roll() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Sun)
+            .player_mod(batter, Mod::Flinch, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(batter);
+        game.trace_rolls();
+
+        let labels = |world: &World, game: &Game, rng: &mut Rng| {
+            game.rng_trace.as_ref().unwrap().borrow_mut().clear();
+            let mut detail = PitchDetail::default();
+            do_pitch(world, game, rng, &mut detail);
+            assert!(!detail.swung);
+            game.rng_trace.as_ref().unwrap().borrow().iter().map(|(label, _)| label.clone()).collect::<Vec<String>>()
+        };
+        assert_eq!(labels(&world, &game, &mut rng), ["strike"]);
+
+        world.ruleset.flinch_swings = true;
+        assert_eq!(labels(&world, &game, &mut rng), ["strike", "swing"]);
+    }
+
+    #[test]
+    fn sample_pitch_outcomes_neutral_matchup() {
+        let mut rng = Rng::new(69, 420);
+        let batter = Player::new(&mut rng);
+        let pitcher = Player::new(&mut rng);
+        let tally = sample_pitch_outcomes(&batter, &pitcher, 12, 10000, &mut rng);
+        assert_eq!(tally.values().sum::<usize>(), 10000);
+        for kind in [PitchOutcomeKind::StrikeSwinging, PitchOutcomeKind::StrikeLooking, PitchOutcomeKind::Ball, PitchOutcomeKind::Single] {
+            assert!(tally.get(&kind).is_some_and(|&count| count > 0), "no {:?}", kind);
+        }
+    }
+
+    //a reverberating batter still gets rolled for once the inning's used up its reverbs
+    #[test]
+    fn reverberating_rolls_past_the_limit() {
+        let draws_for = |reverberating: bool| {
+            let (mut world, mut rng, home, away) = test_world(12);
+            let prev = world.team(away).lineup[0];
+            let mut scenario = GameScenario::new(home, away).weather(Weather::Sun);
+            if reverberating {
+                scenario = scenario.player_mod(prev, Mod::Reverberating, ModLifetime::Permanent);
+            }
+            let mut game = scenario.build(&mut world, &mut rng);
+            game.started = true;
+            game.events.add(String::from("Strike"));
+            game.scoreboard.away_team.batter_index = 1;
+            game.reverberations_inning = REVERBERATING_LIMIT;
+            let draws = rng.draws();
+            let evt = BatterStatePlugin.tick(&game, &world, &mut rng);
+            assert!(!matches!(evt, Some(Event::Reverberating { .. })));
+            rng.draws() - draws
+        };
+        assert_eq!(draws_for(true), draws_for(false) + 1);
+    }
+
+    //bottom of the 9th, tied, solo shot: with walk-offs on that's the game, home wins
+    #[test]
+    fn walk_off_home_run() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        world.walk_offs = true;
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.inning = 9;
+        game.scoreboard.top = false;
+        game.scoreboard.home_team.score = 3.0;
+        game.scoreboard.away_team.score = 3.0;
+        game.scoreboard.home_team.batter = Some(world.team(home).lineup[0]);
+
+        Event::HomeRun.apply(&mut game, &mut world);
+        assert_eq!(game.outs, 0);
+        let event = InningStatePlugin.tick(&game, &world, &mut rng);
+        assert!(matches!(event, Some(Event::GameOver)));
+        event.unwrap().apply(&mut game, &mut world);
+        assert_eq!(world.team(home).wins, 1);
+        assert_eq!(world.team(away).losses, 1);
+
+        //without them the half plays on
+        world.walk_offs = false;
+        assert!(InningStatePlugin.tick(&game, &world, &mut rng).is_none());
+    }
+
+    //a budget too small for a whole game comes back as an error, with the game left where it stopped
+    #[test]
+    fn play_stops_at_the_event_budget() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        let err = sim.play(&mut game, 5).unwrap_err();
+        assert_eq!(err.events, 5);
+        assert_eq!((err.inning, err.top, err.outs), (game.inning, game.scoreboard.top, game.outs));
+        assert_eq!(game.events.total(), 5);
+
+        let events = sim.play(&mut game, 100000).unwrap();
+        assert!(events > 0);
+        assert_eq!(game.events.last(), "GameOver");
+    }
+
+    #[test]
+    #[should_panic(expected = "over the ceiling")]
+    fn max_total_runs_panics_past_the_ceiling() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.home_team.score = 3.0;
+        game.scoreboard.away_team.score = 3.0;
+        let mut sim = Sim::new(&mut world, &mut rng);
+        sim.max_total_runs = Some(5.0);
+        sim.next(&game);
+    }
+
+    #[test]
+    fn max_fouls_ends_the_pa() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        game.strikes = 2;
+        game.fouls = 2;
+        assert!(matches!(outcome_event(PitchOutcome::Foul, &game, &world, &mut rng), Event::Foul));
+        game.max_fouls = Some(3);
+        assert!(matches!(outcome_event(PitchOutcome::Foul, &game, &world, &mut rng), Event::Foul));
+        game.fouls = 3;
+        assert!(matches!(outcome_event(PitchOutcome::Foul, &game, &world, &mut rng), Event::Strikeout));
+    }
+
+    //feedback between two soundproof players: BothSoundproof, and nobody moves
+    #[test]
+    fn feedback_both_soundproof() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        let mut scenario = GameScenario::new(home, away).weather(Weather::Feedback);
+        for team in [home, away] {
+            let team = world.team(team);
+            for &player in team.lineup.iter().chain(team.rotation.iter()) {
+                scenario = scenario.player_mod(player, Mod::Soundproof, ModLifetime::Permanent);
+            }
+        }
+        let mut game = scenario
+            .player_mod(batter, Mod::SuperFlickering, ModLifetime::Permanent)
+            .player_mod(world.team(home).rotation[0], Mod::SuperFlickering, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(batter);
+
+        let event = (0..10000).find_map(|_| WeatherPlugin.tick(&game, &world, &mut rng)).unwrap();
+        assert!(matches!(event, Event::BothSoundproof { .. }), "{}", event);
+        let home_lineup = world.team(home).lineup.clone();
+        let away_lineup = world.team(away).lineup.clone();
+        event.apply(&mut game, &mut world);
+        assert_eq!(world.team(home).lineup, home_lineup);
+        assert_eq!(world.team(away).lineup, away_lineup);
+        assert_eq!(game.batter(), Some(batter));
+    }
+
+    //there's no run_game to compare against, so the same seed played the long way round
+    //(play, with a full event log) has to end up with the same result
+    #[test]
+    fn stats_only_matches_a_full_game() {
+        let result = |stats_only: bool| {
+            let (mut world, mut rng, home, away) = test_world(12);
+            let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+            let mut sim = Sim::new(&mut world, &mut rng);
+            let result = if stats_only {
+                sim.run_game_stats_only(&mut game)
+            } else {
+                game.events = crate::events::Events::new();
+                sim.play(&mut game, 100000).unwrap();
+                game.result().unwrap()
+            };
+            (result.winning_team, result.losing_team, result.winning_score, result.losing_score, result.winning_pitcher, result.losing_pitcher)
+        };
+        assert_eq!(result(true), result(false));
+    }
+
+    //says which plugin it was through the batter id
+    struct Tagged(u32, u128);
+    impl Plugin for Tagged {
+        fn priority(&self) -> u32 {
+            self.0
+        }
+
+        fn tick(&self, _game: &Game, _world: &World, _rng: &mut Rng) -> Option<Event> {
+            Some(Event::BatterUp { batter: Uuid::from_u128(self.1) })
+        }
+    }
+
+    #[test]
+    fn add_plugin_orders_by_priority_then_insertion() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.started = true;
+        game.events.add(String::from("Strike"));
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        let tag = |event: Event| match event {
+            Event::BatterUp { batter } => batter.as_u128(),
+            _ => panic!("expected a tagged plugin, got {}", event),
+        };
+
+        sim.add_plugin(Box::new(Tagged(95, 1)));
+        sim.add_plugin(Box::new(Tagged(95, 2)));
+        assert_eq!(tag(sim.next(&game)), 1);
+        sim.add_plugin(Box::new(Tagged(94, 3)));
+        assert_eq!(tag(sim.next(&game)), 3);
+
+        let priorities: Vec<u32> = sim.plugins.iter().map(|p| p.priority()).collect();
+        assert!(priorities.windows(2).all(|w| w[0] <= w[1]), "{:?}", priorities);
+    }
+
+    fn runner_with_speed(speed: f64, rng: &mut Rng) -> Player {
+        let mut runner = Player::new(rng);
+        runner.base_thirst = speed;
+        runner.continuation = speed;
+        runner.ground_friction = speed;
+        runner.indulgence = speed;
+        runner.laserlikeness = speed;
+        runner
+    }
+
+    #[test]
+    fn simulate_steals_counts() {
+        let mut rng = Rng::new(69, 420);
+        let runner = runner_with_speed(0.5, &mut rng);
+        let defender = Player::new(&mut rng);
+        let (attempts, successes) = simulate_steals(&runner, &defender, 100000, &mut rng);
+        assert!(successes <= attempts);
+        let attempt_rate = attempts as f64 / 100000.0;
+        let success_rate = successes as f64 / attempts as f64;
+        assert!((attempt_rate - formulas::steal_attempt_threshold(&runner, &defender)).abs() < 0.01);
+        assert!((success_rate - formulas::steal_success_threshold(&runner, &defender)).abs() < 0.02);
+    }
+
+    #[test]
+    #[ignore = "steal_success_threshold is a flat placeholder, it doesn't look at the runner yet"]
+    fn fast_runners_steal_more() {
+        let mut rng = Rng::new(69, 420);
+        let fast = runner_with_speed(1.0, &mut rng);
+        let slow = runner_with_speed(0.0, &mut rng);
+        let defender = Player::new(&mut rng);
+        let (fast_attempts, fast_successes) = simulate_steals(&fast, &defender, 100000, &mut rng);
+        let (slow_attempts, slow_successes) = simulate_steals(&slow, &defender, 100000, &mut rng);
+        assert!(fast_successes as f64 / fast_attempts as f64 > slow_successes as f64 / slow_attempts as f64);
+    }
+
+    //two fire eaters on the same rolls, once with each of them unstable: whichever's
+    //unstable gets first go at the flame, so they're the one who eats it either way
+    #[test]
+    fn unstable_fire_eater_goes_first() {
+        let eater = |unstable_batter: bool| {
+            let (mut world, mut rng, home, away) = test_world(12);
+            let batter = world.team(away).lineup[0];
+            let pitcher = world.team(home).rotation[0];
+            let unstable = if unstable_batter { batter } else { pitcher };
+            let mut game = GameScenario::new(home, away)
+                .weather(Weather::Eclipse)
+                .player_mod(batter, Mod::FireEater, ModLifetime::Permanent)
+                .player_mod(pitcher, Mod::FireEater, ModLifetime::Permanent)
+                .player_mod(unstable, Mod::Unstable, ModLifetime::Permanent)
+                .build(&mut world, &mut rng);
+            game.scoreboard.away_team.batter = Some(batter);
+            assert_eq!(game.pitcher(), pitcher);
+            let target = (0..100000)
+                .find_map(|_| match WeatherPlugin.tick(&game, &world, &mut rng) {
+                    Some(Event::FireEater { target }) => Some(target),
+                    _ => None,
+                })
+                .unwrap();
+            (target, unstable)
+        };
+        let (target, unstable) = eater(true);
+        assert_eq!(target, unstable);
+        let (target, unstable) = eater(false);
+        assert_eq!(target, unstable);
+    }
+
+    //both teams sealed, so whichever way the drain goes it's blocked: counted, and the target keeps their stats
+    #[test]
+    fn drain_into_sealant_is_blocked() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away)
+            .weather(Weather::Blooddrain)
+            .team_mod(home, Mod::Sealant, ModLifetime::Permanent)
+            .team_mod(away, Mod::Sealant, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+
+        let event = (0..100000).find_map(|_| WeatherPlugin.tick(&game, &world, &mut rng)).unwrap();
+        let target = match event {
+            Event::BlockedDrain { target, .. } => target,
+            _ => panic!("expected a blocked drain, got {}", event),
+        };
+        let team = world.player(target).team.unwrap();
+        let stats = |world: &World| {
+            let player = world.player(target);
+            (player.batting(), player.pitching(), player.defense(), player.baserunning())
+        };
+        let before = stats(&world);
+        event.apply(&mut game, &mut world);
+        assert_eq!(world.team(team).blocked_drains, 1);
+        assert_eq!(stats(&world), before);
+        assert!(world.team(team).mods.has(Mod::Sealant));
+    }
+
+
+    //a seven inning exhibition: over after the 7th, but not after the 6th
+    #[test]
+    fn seven_inning_game_ends_after_the_seventh() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        world.innings = 7;
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.home_team.score = 2.0;
+        game.scoreboard.away_team.score = 1.0;
+        game.outs = 3;
+
+        game.inning = 6;
+        game.scoreboard.top = false;
+        assert!(matches!(InningStatePlugin.tick(&game, &world, &mut rng), Some(Event::InningSwitch { inning: 7, top: true, .. })));
+
+        //home's ahead after the top of the 7th, so the bottom doesn't get played
+        game.inning = 7;
+        game.scoreboard.top = true;
+        assert!(matches!(InningStatePlugin.tick(&game, &world, &mut rng), Some(Event::GameOver)));
+
+        //played out for real, games finish in the 7th (or extras if it's tied)
+        let mut innings = vec![];
+        for _ in 0..5 {
+            let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+            Sim::new(&mut world, &mut rng).play(&mut game, 100000).unwrap();
+            innings.push(game.inning);
+        }
+        assert!(innings.iter().all(|&inning| inning >= 7));
+        assert!(innings.contains(&7), "{:?}", innings);
+    }
+
+
+    //end of the 9th: home ahead wins, a tie goes to the 10th, and a float
+    //hair's breadth off a tie is still a tie
+    #[test]
+    fn end_of_regulation() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.inning = 9;
+        game.scoreboard.top = false;
+        game.outs = 3;
+
+        game.scoreboard.home_team.score = 4.0;
+        game.scoreboard.away_team.score = 3.0;
+        assert_eq!(game.lead(), Ordering::Greater);
+        assert!(matches!(InningStatePlugin.tick(&game, &world, &mut rng), Some(Event::GameOver)));
+
+        game.scoreboard.home_team.score = 3.0;
+        assert_eq!(game.lead(), Ordering::Equal);
+        assert!(matches!(InningStatePlugin.tick(&game, &world, &mut rng), Some(Event::InningSwitch { inning: 10, top: true, .. })));
+
+        game.scoreboard.home_team.score = 3.0000001;
+        assert_eq!(game.lead(), Ordering::Equal);
+        assert!(matches!(InningStatePlugin.tick(&game, &world, &mut rng), Some(Event::InningSwitch { inning: 10, top: true, .. })));
+
+        //still tied after the top of the 10th, so the bottom gets played
+        game.inning = 10;
+        game.scoreboard.top = true;
+        assert!(matches!(InningStatePlugin.tick(&game, &world, &mut rng), Some(Event::InningSwitch { inning: 10, top: false, .. })));
+    }
+
+
+    //two strikes, then keep throwing the same pitch until they strike out:
+    //swinging ones come back swung with no contact, looking ones unswung strikes
+    #[test]
+    fn strikeout_pitch_detail() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        while game.batter().is_none() {
+            let evt = sim.next(&game);
+            evt.apply(&mut game, sim.world);
+        }
+        game.strikes = 2;
+
+        let (mut swinging, mut looking) = (0, 0);
+        for _ in 0..1000 {
+            if !matches!(sim.next(&game), Event::Strikeout) {
+                continue;
+            }
+            let detail = sim.last_pitch().unwrap();
+            assert!(!detail.contact && !detail.foul && !detail.out && !detail.fly);
+            if detail.swung {
+                swinging += 1;
+            } else {
+                assert!(detail.strike);
+                looking += 1;
+            }
+        }
+        assert!(swinging > 0 && looking > 0);
+    }
+
+
+    //an electric batting team zaps a strike away at electric_threshold's rate, and never without one
+    #[test]
+    fn electric_zaps_at_the_threshold_rate() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .team_mod(away, Mod::Electric, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+
+        let zaps = |game: &Game, rng: &mut Rng| (0..10000)
+            .filter(|_| matches!(ModPlugin.tick(game, &world, rng), Some(Event::Zap { batter: true })))
+            .count();
+        assert_eq!(zaps(&game, &mut rng), 0);
+
+        game.strikes = 1;
+        let rate = zaps(&game, &mut rng) as f64 / 10000.0;
+        let threshold = formulas::electric_threshold(world.ruleset.season);
+        assert!((rate - threshold).abs() < 0.02, "zapped {} of the time, expected {}", rate, threshold);
+    }
+
+
+    //haunted with nobody in the hall to inhabit them: they just come up and bat
+    #[test]
+    fn haunted_with_an_empty_hall_bats_normally() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .player_mod(batter, Mod::Haunted, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        world.hall.clear();
+
+        for _ in 0..100 {
+            let evt = BatterStatePlugin.tick(&game, &world, &mut rng);
+            assert!(matches!(evt, Some(Event::BatterUp { batter: b }) if b == batter));
+        }
+        BatterStatePlugin.tick(&game, &world, &mut rng).unwrap().apply(&mut game, &mut world);
+        assert_eq!(game.batter(), Some(batter));
+        let mut sim = Sim::new(&mut world, &mut rng);
+        sim.play(&mut game, 100000).unwrap();
+
+        //with someone in there they do get inhabited sometimes
+        let (mut world, mut rng, home, away) = test_world(12);
+        let game = GameScenario::new(home, away).weather(Weather::Sun)
+            .player_mod(batter, Mod::Haunted, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        let ghost = world.team(home).lineup[0];
+        world.hall.push(ghost);
+        assert!((0..100).any(|_| matches!(BatterStatePlugin.tick(&game, &world, &mut rng), Some(Event::Inhabiting { .. }))));
+    }
+
+
+    //strikes out every away batter, home bats as usual
+    struct AwayShutout;
+    impl Plugin for AwayShutout {
+        fn tick(&self, game: &Game, _world: &World, _rng: &mut Rng) -> Option<Event> {
+            if game.scoreboard.top { Some(Event::Strikeout) } else { None }
+        }
+    }
+
+    #[test]
+    fn compare_models_diffs() {
+        let (world, _, home, away) = test_world(12);
+        let matchup = crate::schedule::Matchup { home_team: home, away_team: away, weather: Some(Weather::Sun) };
+
+        let same = compare_models(&world, &matchup, 0, (69, 420), None, None);
+        assert_eq!(same.first_difference, None);
+        assert!(same.event_a.is_none() && same.event_b.is_none());
+        assert_eq!((same.home_score_difference, same.away_score_difference), (0.0, 0.0));
+
+        let diff = compare_models(&world, &matchup, 0, (69, 420), None, Some(Box::new(AwayShutout)));
+        assert!(diff.first_difference.is_some());
+        assert_eq!(diff.event_b.as_deref(), Some("Strikeout"));
+        assert_ne!(diff.event_a, diff.event_b);
+        assert!(diff.away_score_difference <= 0.0);
+        assert!(diff.home_score_difference != 0.0 || diff.away_score_difference != 0.0);
+
+        //neither run touched the world
+        assert_eq!((world.team(home).wins, world.team(away).wins), (0, 0));
+    }
+
+
+    //a ghost bats for one PA, then the haunted batter's back in their spot for the next time through
+    #[test]
+    fn inhabiting_lasts_one_pa() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let ghost = world.team(home).lineup[0];
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .player_mod(lineup[0], Mod::Haunted, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+
+        Event::Inhabiting { batter: lineup[0], inhabit: ghost }.apply(&mut game, &mut world);
+        assert_eq!(game.batter(), Some(ghost));
+        assert_eq!(game.scoreboard.away_team.inhabited, Some(lineup[0]));
+        Event::Strikeout.apply(&mut game, &mut world);
+        assert_eq!(game.batter(), None);
+        assert_eq!(game.scoreboard.away_team.inhabited, None);
+        assert_eq!(game.scoreboard.away_team.batter_index, 1);
+        assert_eq!(world.team(away).lineup, lineup);
+
+        //around the order and back to them, nobody in the hall this time
+        game.scoreboard.away_team.batter_index = lineup.len();
+        let evt = BatterStatePlugin.tick(&game, &world, &mut rng).unwrap();
+        assert!(matches!(evt, Event::BatterUp { batter } if batter == lineup[0]));
+        evt.apply(&mut game, &mut world);
+        assert_eq!(game.batter(), Some(lineup[0]));
+    }
+
+
+    //a charm batter with the roll forced: walked on the first pitch, not on any later one
+    #[test]
+    fn charm_batter_walks_on_the_first_pitch() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        world.ruleset.charm.base = 1.0;
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .player_mod(batter, Mod::Charm, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        while game.batter().is_none() {
+            let evt = sim.next(&game);
+            evt.apply(&mut game, sim.world);
+        }
+        assert_eq!(game.batter(), Some(batter));
+
+        game.balls = 1;
+        assert!(!matches!(sim.next(&game), Event::CharmWalk));
+        game.balls = 0;
+
+        let evt = sim.next(&game);
+        assert!(matches!(evt, Event::CharmWalk));
+        evt.apply(&mut game, sim.world);
+        assert_eq!(game.runners.at(0), Some(batter));
+        assert_eq!(game.batter(), None);
+    }
+
+
+    //superyummy's performing comes tagged with it, over in peanuts and under otherwise
+    #[test]
+    fn superyummy_performing_reason() {
+        for (weather, over) in [(Weather::Peanuts, true), (Weather::Sun, false)] {
+            let (mut world, mut rng, home, away) = test_world(12);
+            let player = world.team(away).lineup[2];
+            let game = GameScenario::new(home, away).weather(weather)
+                .player_mod(player, Mod::Superyummy, ModLifetime::Permanent)
+                .build(&mut world, &mut rng);
+            match PregamePlugin.tick(&game, &world, &mut rng) {
+                Some(Event::Performing { overperforming, underperforming }) => {
+                    let (yes, no) = if over { (overperforming, underperforming) } else { (underperforming, overperforming) };
+                    assert_eq!(yes, vec![(player, Mod::Superyummy)]);
+                    assert!(no.is_empty());
+                },
+                evt => panic!("expected performing, got {:?}", evt),
+            }
+        }
+    }
+
+
+    //a two strike foul with a roll under foul_tip_threshold is caught for strike three,
+    //but only with foul tips on. with them off there's no roll at all
+    #[test]
+    fn two_strike_foul_tip() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        game.strikes = 2;
+
+        let threshold = formulas::foul_tip_threshold(world.ruleset.season);
+        let seed = (0..).find(|&s| Rng::new(s, 420).next() < threshold).unwrap();
+
+        let mut rng = Rng::new(seed, 420);
+        assert!(matches!(outcome_event(PitchOutcome::Foul, &game, &world, &mut rng), Event::Foul));
+        assert_eq!(rng.draws(), 0);
+
+        world.foul_tips = true;
+        let mut rng = Rng::new(seed, 420);
+        assert!(matches!(outcome_event(PitchOutcome::Foul, &game, &world, &mut rng), Event::Strikeout));
+
+        //and never on the first or second strike
+        game.strikes = 1;
+        let mut rng = Rng::new(seed, 420);
+        assert!(matches!(outcome_event(PitchOutcome::Foul, &game, &world, &mut rng), Event::Foul));
+    }
+
+
+    //a ground out with the bases empty: every roll do_pitch made, labeled, in order
+    #[test]
+    fn rng_trace_ground_out() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        game.trace_rolls();
+
+        let mut detail = PitchDetail::default();
+        let draws = loop {
+            game.rng_trace.as_ref().unwrap().borrow_mut().clear();
+            let draws = rng.draws();
+            if let PitchOutcome::GroundOut { .. } = do_pitch(&world, &game, &mut rng, &mut detail) {
+                break rng.draws() - draws;
+            }
+        };
+        let trace = game.rng_trace.as_ref().unwrap().borrow();
+        let labels: Vec<&str> = trace.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, ["strike", "swing", "contact", "foul", "out_defender", "out", "fly_defender", "fly", "ground_defender"]);
+        assert_eq!(draws, trace.len() as u64);
+    }
+
+
+    //runner on third tags up on a fly ball with one out: run in, rbi to the batter
+    #[test]
+    fn sac_fly_scores_and_credits_an_rbi() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .runner(2, lineup[1])
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        game.outs = 1;
+
+        let mut advancing_runners = RunnerSet::new();
+        advancing_runners.push(lineup[1]);
+        let fielder = world.team(home).lineup[0];
+        let event = outcome_event(PitchOutcome::Flyout { fielder, advancing_runners }, &game, &world, &mut rng);
+        event.apply(&mut game, &mut world);
+
+        assert_eq!(game.outs, 2);
+        assert_eq!(game.scoreboard.away_team.score, 1.0);
+        assert!(game.runners.empty());
+        assert_eq!(game.rbis.get(&lineup[0]), Some(&1));
+    }
+
+
+    //nobody left for the eclipse to pick (empty lineups, both pitchers on base): no event, no panic
+    #[test]
+    fn eclipse_with_nobody_eligible() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Eclipse)
+            .runner(0, world.team(home).rotation[0])
+            .runner(1, world.team(away).rotation[0])
+            .build(&mut world, &mut rng);
+        assert!(game.runners.contains(game.scoreboard.home_team.pitcher) && game.runners.contains(game.scoreboard.away_team.pitcher));
+        //the batter's out of the lineup too
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+        world.team_mut(home).lineup.clear();
+        world.team_mut(away).lineup.clear();
+        for _ in 0..1000 {
+            assert!(WeatherPlugin.tick(&game, &world, &mut rng).is_none());
+        }
+    }
+
+
+    //same rolls, same target: life of the party gets a fifth more on every stat
+    #[test]
+    fn life_of_the_party_boosts_more() {
+        let party = |life_of_party: bool| {
+            let (mut world, mut rng, home, away) = test_world(12);
+            let game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+            world.ruleset.party = 1.0;
+            for team in [home, away] {
+                world.team_mut(team).partying = true;
+                if life_of_party {
+                    let roster: Vec<Uuid> = world.team(team).lineup.iter().chain(world.team(team).rotation.iter()).copied().collect();
+                    for id in roster {
+                        world.give_mod(id, Mod::LifeOfTheParty, ModLifetime::Permanent);
+                    }
+                }
+            }
+            match PartyPlugin.tick(&game, &world, &mut Rng::new(69, 420)) {
+                Some(Event::Party { target, boosts }) => (target, boosts),
+                evt => panic!("expected a party, got {:?}", evt),
+            }
+        };
+        let (normal_target, normal) = party(false);
+        let (life_target, life) = party(true);
+        assert_eq!(normal_target, life_target);
+        for (n, l) in normal.iter().zip(life.iter()) {
+            assert!(l > n);
+            assert!((l / n - 1.2).abs() < 1e-9);
+        }
+    }
+
+
+    //electric on just the batter zaps strikes, on just the pitcher zaps balls
+    #[test]
+    fn player_electric_zaps() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .player_mod(batter, Mod::Electric, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(batter);
+        game.strikes = 1;
+        game.balls = 1;
+        assert!(!world.team(away).mods.has(Mod::Electric));
+        let zapped = |game: &Game, world: &World, rng: &mut Rng, batter: bool| (0..1000)
+            .any(|_| matches!(ModPlugin.tick(game, world, rng), Some(Event::Zap { batter: b }) if b == batter));
+        assert!(zapped(&game, &world, &mut rng, true));
+        assert!(!zapped(&game, &world, &mut rng, false));
+
+        world.player_mut(batter).mods.remove(Mod::Electric);
+        world.player_mut(game.pitcher()).mods.add(Mod::Electric, ModLifetime::Permanent);
+        assert!(zapped(&game, &world, &mut rng, false));
+        assert!(!zapped(&game, &world, &mut rng, true));
+    }
+
+
+    //a feedback swap clears the poll cache, and what gets cached next is what a fresh poll says
+    #[test]
+    fn poll_cache_after_feedback() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let batter = world.team(away).lineup[0];
+        let fielder = world.team(home).lineup[0];
+        let mut game = GameScenario::new(home, away).weather(Weather::Feedback)
+            .player_mod(fielder, Mod::Superyummy, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(batter);
+
+        assert_eq!(poll_for_mod(&game, &world, Mod::Superyummy, "current", false), vec![fielder]);
+        assert!(!game.poll_cache.borrow().is_empty());
+
+        Event::Feedback { target1: batter, target2: fielder }.apply(&mut game, &mut world);
+        assert!(game.poll_cache.borrow().is_empty());
+        for exclusion in ["current", "all"] {
+            let fresh = poll_for_mod_uncached(&game, &world, Mod::Superyummy, exclusion, false);
+            assert_eq!(poll_for_mod(&game, &world, Mod::Superyummy, exclusion, false), fresh);
+            assert_eq!(poll_for_mod(&game, &world, Mod::Superyummy, exclusion, false), fresh);
+        }
+        assert!(world.team(away).lineup.contains(&fielder));
+    }
+
+
+    #[test]
+    fn runs_for_and_against_match_the_final_score() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        Sim::new(&mut world, &mut rng).play(&mut game, 100000).unwrap();
+        let (home_score, away_score) = (game.scoreboard.home_team.score, game.scoreboard.away_team.score);
+        assert_eq!((world.team(home).runs_for, world.team(home).runs_against), (home_score, away_score));
+        assert_eq!((world.team(away).runs_for, world.team(away).runs_against), (away_score, home_score));
+        assert_eq!(world.team(home).run_differential(), home_score - away_score);
+        assert_eq!(world.team(away).run_differential(), -world.team(home).run_differential());
+    }
+
+
+    //birds and an elsewhere return don't depend on each other, so they come out of the same tick
+    #[test]
+    fn next_batch_birds_and_elsewhere_return() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let gone = world.team(away).lineup[3];
+        let mut game = GameScenario::new(home, away).weather(Weather::Birds)
+            .player_mod(gone, Mod::Elsewhere, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+        world.player_mut(gone).swept_on = Some(game.day);
+        world.ruleset.elsewhere_return = 1.0;
+        game.started = true;
+        game.events.add(String::from("Strike"));
+        game.scoreboard.away_team.batter = Some(world.team(away).lineup[0]);
+
+        let mut sim = Sim::new(&mut world, &mut rng);
+        let batch = (0..1000)
+            .map(|_| sim.next_batch(&game))
+            .find(|batch| matches!(batch[0], Event::Birds))
+            .expect("no birds");
+        assert_eq!(batch.len(), 2);
+        assert!(matches!(&batch[1], Event::ElsewhereReturn { returned, .. } if *returned == vec![gone]));
+
+        for event in batch {
+            event.apply(&mut game, sim.world);
+        }
+        assert!(!sim.world.player(gone).mods.has(Mod::Elsewhere));
+        assert_eq!(game.events.last(), "ElsewhereReturn");
+    }
+
+
+    //friend of crows on both mounds in the birds: an ambush ends the pa, so there's never two in one,
+    //and each one goes down as a strikeout for the pitcher
+    #[test]
+    fn crow_ambush_at_most_once_per_pa() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut ambushes = 0;
+        for _ in 0..3 {
+            let mut game = GameScenario::new(home, away).weather(Weather::Birds).build(&mut world, &mut rng);
+            for pitcher in [game.scoreboard.home_team.pitcher, game.scoreboard.away_team.pitcher] {
+                world.give_mod(pitcher, Mod::FriendOfCrows, ModLifetime::Game);
+            }
+            let mut sim = Sim::new(&mut world, &mut rng);
+            let mut this_pa = 0;
+            let mut strikeouts = 0;
+            loop {
+                let evt = sim.next(&game);
+                match evt {
+                    Event::BatterUp { .. } => this_pa = 0,
+                    Event::CrowAmbush => {
+                        this_pa += 1;
+                        ambushes += 1;
+                        assert_eq!(this_pa, 1);
+                    },
+                    _ => {}
+                }
+                if matches!(evt, Event::Strikeout | Event::CharmStrikeout | Event::CrowAmbush) {
+                    strikeouts += 1;
+                }
+                evt.apply(&mut game, sim.world);
+                if let Event::GameOver = evt {
+                    break;
+                }
+            }
+            assert_eq!(game.strikeouts.values().map(|&k| k as u32).sum::<u32>(), strikeouts);
+        }
+        assert!(ambushes > 0);
+    }
+
+
+    //a few turns through the order, everyone striking out: the shelled one never comes up,
+    //the reverberating one sometimes goes twice in a row, and otherwise it's lineup order
+    #[test]
+    fn lineup_turn_with_shelled_and_reverberating() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .player_mod(lineup[2], Mod::Shelled, ModLifetime::Permanent)
+            .player_mod(lineup[4], Mod::Reverberating, ModLifetime::Permanent)
+            .build(&mut world, &mut rng);
+
+        let mut batters = vec![];
+        for _ in 0..200 {
+            while game.batter().is_none() {
+                BatterStatePlugin.tick(&game, &world, &mut rng).unwrap().apply(&mut game, &mut world);
+            }
+            batters.push(game.batter().unwrap());
+            Event::Strikeout.apply(&mut game, &mut world);
+            game.reverberations_inning = 0;
+        }
+
+        let up_next = |prev: Uuid| {
+            let idx = lineup.iter().position(|&id| id == prev).unwrap();
+            let next = lineup[(idx + 1) % lineup.len()];
+            if next == lineup[2] { lineup[(idx + 2) % lineup.len()] } else { next }
+        };
+        assert_eq!(batters[0], lineup[0]);
+        let mut repeats = 0;
+        for pair in batters.windows(2) {
+            if pair[0] == pair[1] {
+                assert_eq!(pair[0], lineup[4]);
+                repeats += 1;
+            } else {
+                assert_eq!(pair[1], up_next(pair[0]));
+            }
+        }
+        assert!(!batters.contains(&lineup[2]));
+        assert!(repeats > 0);
+    }
+
+
+    //one plate appearance played out: the count goes up once for every pitch thrown in it
+    #[test]
+    fn pitch_count_across_a_pa() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+        let mut sim = Sim::new(&mut world, &mut rng);
+        while game.batter().is_none() {
+            let evt = sim.next(&game);
+            evt.apply(&mut game, sim.world);
+        }
+        assert_eq!(game.pitch_count(), 0);
+
+        let mut pitches = 0;
+        while game.batter().is_some() {
+            let evt = sim.next(&game);
+            if evt.is_pitch() {
+                pitches += 1;
+            }
+            evt.apply(&mut game, sim.world);
+            assert_eq!(game.pitch_count(), pitches);
+        }
+        assert!(pitches > 0);
+    }
+
+
+    //runner on second and first, and what each kind of outcome turns into from there
+    #[test]
+    fn outcome_event_mappings() {
+        let (mut world, mut rng, home, away) = test_world(12);
+        let lineup = world.team(away).lineup.clone();
+        let fielder = world.team(home).lineup[0];
+        let (second, first) = (lineup[1], lineup[2]);
+        let mut game = GameScenario::new(home, away).weather(Weather::Sun)
+            .runner(1, second)
+            .runner(0, first)
+            .build(&mut world, &mut rng);
+        game.scoreboard.away_team.batter = Some(lineup[0]);
+        let runners_set = |ids: &[Uuid]| {
+            let mut set = RunnerSet::new();
+            for &id in ids {
+                set.push(id);
+            }
+            set
+        };
+        //where everyone ends up, lead runner first
+        let runners_after = |event: &Event| match event {
+            Event::BaseHit { runners_after, .. }
+            | Event::GroundOut { runners_after, .. }
+            | Event::Flyout { runners_after, .. }
+            | Event::DoublePlay { runners_after }
+            | Event::FieldersChoice { runners_after } => runners_after.iter().map(|r| (r.base, r.id)).collect::<Vec<_>>(),
+            _ => panic!("{} doesn't move runners", event),
+        };
+        let mut map = |outcome: PitchOutcome, game: &Game| outcome_event(outcome, game, &world, &mut rng);
+
+        let event = map(PitchOutcome::DoublePlay { runner_out: 1 }, &game);
+        assert!(matches!(event, Event::DoublePlay { .. }));
+        assert_eq!(runners_after(&event), [(1, first)]);
+
+        let event = map(PitchOutcome::FieldersChoice { runner_out: 1 }, &game);
+        assert!(matches!(event, Event::FieldersChoice { .. }));
+        assert_eq!(runners_after(&event), [(1, first)]);
+
+        let event = map(PitchOutcome::GroundOut { fielder, advancing_runners: runners_set(&[second]) }, &game);
+        assert!(matches!(event, Event::GroundOut { fielder: f, .. } if f == fielder));
+        assert_eq!(runners_after(&event), [(2, second), (0, first)]);
+
+        //everyone moves up as many as the batter, then the advancing ones take one more
+        let event = map(PitchOutcome::Single { advancing_runners: runners_set(&[second]) }, &game);
+        assert!(matches!(event, Event::BaseHit { bases: 1, .. }));
+        assert_eq!(runners_after(&event), [(3, second), (1, first)]);
+
+        let event = map(PitchOutcome::Double { advancing_runners: RunnerSet::new() }, &game);
+        assert!(matches!(event, Event::BaseHit { bases: 2, .. }));
+        assert_eq!(runners_after(&event), [(3, second), (2, first)]);
+
+        let event = map(PitchOutcome::Triple { advancing_runners: RunnerSet::new() }, &game);
+        assert!(matches!(event, Event::BaseHit { bases: 3, .. }));
+        assert!(runners_after(&event).iter().all(|&(base, _)| base >= 3));
+
+        assert!(matches!(map(PitchOutcome::HomeRun, &game), Event::HomeRun));
+
+        //the count decides balls and strikes
+        assert!(matches!(map(PitchOutcome::Ball, &game), Event::Ball));
+        assert!(matches!(map(PitchOutcome::StrikeSwinging, &game), Event::Strike));
+        game.balls = 3;
+        game.strikes = 2;
+        assert!(matches!(map(PitchOutcome::Ball, &game), Event::Walk));
+        assert!(matches!(map(PitchOutcome::StrikeSwinging, &game), Event::Strikeout));
+        assert!(matches!(map(PitchOutcome::StrikeLooking, &game), Event::Strikeout));
+    }
+}
@@ -0,0 +1,71 @@
+//counts heap allocations, so it gets its own test binary for the global allocator.
+//run with cargo test --test allocations -- --ignored --nocapture
+use std::{alloc::{GlobalAlloc, Layout, System}, sync::atomic::{AtomicUsize, Ordering}};
+
+use sandbox::{bases::Baserunners, entities::World, events::Event, rng::Rng, scenario::GameScenario, sim::Sim, Weather};
+use uuid::Uuid;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn allocations() -> usize {
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+#[test]
+#[ignore]
+fn baserunner_allocations() {
+    let mut runners = Baserunners::new(4);
+    for base in 0..3 {
+        runners.add(base, Uuid::from_u128(base as u128 + 1));
+    }
+
+    //what BasePlugin does with the runners on a hit, which used to clone a Vec every time
+    let before = allocations();
+    for _ in 0..10000 {
+        let mut new_runners = runners;
+        new_runners.advance_all(1);
+        new_runners.advance_if(|r| r.base < 3);
+        std::hint::black_box(new_runners);
+    }
+    assert_eq!(allocations() - before, 0);
+
+    //and a whole game, for the per pitch number
+    let mut rng = Rng::new(69, 420);
+    let mut world = World::new(12);
+    let home = world.gen_team(&mut rng, String::from("Home"), String::from("H"));
+    let away = world.gen_team(&mut rng, String::from("Away"), String::from("A"));
+    let mut game = GameScenario::new(home, away).weather(Weather::Sun).build(&mut world, &mut rng);
+    let mut sim = Sim::new(&mut world, &mut rng);
+    let before = allocations();
+    let mut events = 0;
+    loop {
+        let evt = sim.next(&game);
+        evt.apply(&mut game, sim.world);
+        events += 1;
+        if let Event::GameOver = evt {
+            break;
+        }
+    }
+    println!(
+        "{} allocations over {} events, {:.1} per event",
+        allocations() - before,
+        events,
+        (allocations() - before) as f64 / events as f64
+    );
+}
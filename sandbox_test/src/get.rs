@@ -347,8 +347,11 @@ impl ChronTeam {
             losses: 0,
             postseason_wins: 0,
             postseason_losses: 0,
+            runs_for: 0.0,
+            runs_against: 0.0,
             partying: false,
             fate: 0,
+            blocked_drains: 0,
 
             mods: modconvert(&[self.permAttr, self.seasAttr, self.weekAttr, self.gameAttr])
         }
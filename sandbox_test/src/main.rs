@@ -13,6 +13,7 @@ use sandbox::{
 use uuid::Uuid;
 use clap::Parser;
 use std::cmp::Ordering;
+use std::io::Write;
 
 mod schedule;
 mod postseason;
@@ -41,6 +42,12 @@ struct Args {
 fn main() {
     let args = Args::parse();
 
+    //the sandbox crate logs notable events (incinerations, feedback etc.)
+    //RUST_LOG=debug shows more, RUST_LOG=warn silences them
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+        .format(|buf, record| writeln!(buf, "{}", record.args()))
+        .init();
+
     //edit seed
     //let mut rng = Rng::new(69, 420);
     //let mut rng = Rng::new(2200200200200200200, 1234567890987654321);
@@ -155,10 +162,7 @@ fn main() {
                         break;
                     }
                 }
-                sim.world.clear_game();
-                if day % 9 == 8 {
-                    sim.world.clear_weekly();
-                }
+                sim.world.advance_day();
                 
                 let mut party_standings: Vec<i16> = Vec::new();
                 for &t in divisions.iter() {
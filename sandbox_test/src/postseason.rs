@@ -1,4 +1,4 @@
-use sandbox::{entities::World, rng::Rng, Game};
+use sandbox::{entities::{Record, World}, rng::Rng, Game};
 use uuid::Uuid;
 use std::cmp::Ordering;
 
@@ -90,27 +90,23 @@ pub fn update_party(divisions: &Vec<Uuid>, indices: &Vec<usize>, day: usize, wor
         }
     }
 
-    let mut losses1: Vec<i16> = playoff_seeds1
+    let standings1: Vec<(Uuid, Record)> = playoff_seeds1
         .iter()
-        .map(|&id| world.team(id).losses)
+        .map(|&id| (id, world.team(id).record()))
         .collect();
-    losses1.sort();
-    let max_losses1 = losses1.last().unwrap();
-    
-    let mut losses2: Vec<i16> = playoff_seeds2
+    let max_losses1 = standings1.iter().map(|(_, record)| record.losses).max().unwrap();
+
+    let standings2: Vec<(Uuid, Record)> = playoff_seeds2
         .iter()
-        .map(|&id| world.team(id).losses)
+        .map(|&id| (id, world.team(id).record()))
         .collect();
-    losses2.sort();
-    let max_losses2 = losses2.last().unwrap();
+    let max_losses2 = standings2.iter().map(|(_, record)| record.losses).max().unwrap();
 
     for i in 0..league_size {
         let team = world.team_mut(divisions[i]);
         //todo: this doesn't include fate...
-        let max_losses = if i < subleague_size { max_losses1 } else { max_losses2 }; 
-        if (team.losses - max_losses) as i128 > (99 - day) as i128 && !team.partying { 
-            //todo: i128???
-            team.partying = true;
+        let (standings, max_losses) = if i < subleague_size { (&standings1, max_losses1) } else { (&standings2, max_losses2) };
+        if team.update_partying(standings, 99 - day as i16) {
             println!("Partytime: day {}, {}, {} losses, {} max playoff losses", day, team.name, team.losses, max_losses);
         }
     }   